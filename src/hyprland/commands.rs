@@ -1,21 +1,33 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::ops::{Deref, DerefMut, Range};
+use std::sync::Mutex;
+use std::time::Duration;
+
 use anyhow::Context as _;
 use serde::Deserialize;
 use tokio::{
-    io::{self, AsyncReadExt, AsyncWriteExt as _},
+    io::{self, AsyncReadExt as _, AsyncWriteExt as _},
     net::UnixStream,
 };
 
-use super::{WindowAddress, WorkspaceId, dispatch::Dispatcher, hyprland_rundir};
+use super::{
+    ModMask, WindowAddress, WorkspaceId, dispatch::Dispatcher, hyprland_rundir, intern::Interned,
+};
+
+/// How long to wait for hyprland to reply to a command, unless set with [`Command::timeout`].
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(2);
 
 pub struct Command {
     stream: UnixStream,
+    timeout: Duration,
 }
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct Workspace {
     pub id: WorkspaceId,
-    pub name: String,
-    pub monitor: String,
+    pub name: Interned,
+    pub monitor: Interned,
     #[serde(rename = "monitorID")]
     pub monitor_id: i32,
     pub windows: i32,
@@ -24,23 +36,414 @@ pub struct Workspace {
     #[serde(rename = "lastwindow")]
     pub last_window: WindowAddress,
     #[serde(rename = "lastwindowtitle")]
-    pub last_window_title: String,
+    pub last_window_title: Interned,
 }
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct Client {
     pub address: WindowAddress,
-    pub title: String,
+    pub mapped: bool,
+    pub hidden: bool,
+    pub at: (i32, i32),
+    pub size: (i32, i32),
+    pub workspace: WorkspaceRef,
+    pub floating: bool,
     pub monitor: i32,
-    pub workspace: ClientWorkspace,
+    pub class: Interned,
+    pub title: Interned,
+    #[serde(rename = "initialClass")]
+    pub initial_class: Interned,
+    #[serde(rename = "initialTitle")]
+    pub initial_title: Interned,
+    pub pid: i32,
+    pub xwayland: bool,
+    pub pinned: bool,
+    /// The fullscreen state hyprland applies to the window.
+    pub fullscreen: FullscreenState,
+    /// The fullscreen state the client itself believes it is in.
+    #[serde(rename = "fullscreenClient")]
+    pub fullscreen_client: FullscreenState,
+    /// Windows in the same group, including this one. Empty when not grouped.
+    pub grouped: Vec<WindowAddress>,
+    /// How recently the window was focused, 0 being the currently focused one.
+    #[serde(rename = "focusHistoryID")]
+    pub focus_history_id: i32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "u8")]
+pub enum FullscreenState {
+    None,
+    Maximized,
+    Fullscreen,
+    MaximizedFullscreen,
+}
+
+impl TryFrom<u8> for FullscreenState {
+    type Error = String;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::None),
+            1 => Ok(Self::Maximized),
+            2 => Ok(Self::Fullscreen),
+            3 => Ok(Self::MaximizedFullscreen),
+            _ => Err(format!("invalid fullscreen state {value}")),
+        }
+    }
 }
 
 #[derive(Clone, Debug, Deserialize)]
-pub struct ClientWorkspace {
+pub struct Monitor {
+    pub id: i32,
+    pub name: Interned,
+    pub description: String,
+    pub width: u32,
+    pub height: u32,
+    #[serde(rename = "refreshRate")]
+    pub refresh_rate: f64,
+    pub x: i32,
+    pub y: i32,
+    pub scale: f64,
+    #[serde(rename = "activeWorkspace")]
+    pub active_workspace: WorkspaceRef,
+    pub focused: bool,
+    /// Whether the monitor is powered on, as set with [`Dispatcher::Dpms`].
+    #[serde(rename = "dpmsStatus")]
+    pub dpms_status: bool,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct WorkspaceRef {
     pub id: WorkspaceId,
+    pub name: Interned,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Devices {
+    pub mice: Vec<Mouse>,
+    pub keyboards: Vec<Keyboard>,
+    pub tablets: Vec<Tablet>,
+    pub touch: Vec<TouchDevice>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Mouse {
+    pub address: String,
+    pub name: String,
+    #[serde(rename = "defaultSpeed")]
+    pub default_speed: f64,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Keyboard {
+    pub address: String,
+    pub name: String,
+    pub rules: String,
+    pub model: String,
+    pub layout: String,
+    pub variant: String,
+    pub options: String,
+    /// Human readable name of the active layout, as also sent in `activelayout` events.
+    pub active_keymap: String,
+    /// Whether this is the keyboard whose layout hyprland considers current.
+    pub main: bool,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Tablet {
+    pub address: String,
+    /// Not set for tablet pads and tools, which are named after the tablet they belong to.
+    pub name: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct TouchDevice {
+    pub address: String,
     pub name: String,
 }
 
+#[derive(Clone, Debug, Deserialize)]
+pub struct MonitorLayers {
+    pub levels: BTreeMap<LayerLevel, Vec<LayerSurface>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
+#[serde(try_from = "u8")]
+pub enum LayerLevel {
+    Background,
+    Bottom,
+    Top,
+    Overlay,
+}
+
+impl TryFrom<u8> for LayerLevel {
+    type Error = String;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Background),
+            1 => Ok(Self::Bottom),
+            2 => Ok(Self::Top),
+            3 => Ok(Self::Overlay),
+            _ => Err(format!("invalid layer level {value}")),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct LayerSurface {
+    pub address: String,
+    pub x: i32,
+    pub y: i32,
+    pub w: i32,
+    pub h: i32,
+    pub namespace: String,
+    pub pid: i32,
+}
+
+/// A `workspace = ...` rule. Only the properties the rule sets are present.
+#[derive(Clone, Debug, Deserialize)]
+pub struct WorkspaceRule {
+    /// The workspace selector the rule applies to, e.g. `1`, `name:web` or `r[1-5]`.
+    #[serde(rename = "workspaceString")]
+    pub workspace_string: String,
+    pub monitor: Option<String>,
+    pub default: Option<bool>,
+    pub persistent: Option<bool>,
+    /// Top, right, bottom and left gaps.
+    #[serde(rename = "gapsIn")]
+    pub gaps_in: Option<[i32; 4]>,
+    #[serde(rename = "gapsOut")]
+    pub gaps_out: Option<[i32; 4]>,
+    #[serde(rename = "borderSize")]
+    pub border_size: Option<i32>,
+    pub border: Option<bool>,
+    pub rounding: Option<bool>,
+    pub decorate: Option<bool>,
+    pub shadow: Option<bool>,
+    #[serde(rename = "defaultName")]
+    pub default_name: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct GlobalShortcut {
+    /// `appid:id` of the shortcut, as used with the `global` dispatcher.
+    pub name: String,
+    pub description: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Decoration {
+    #[serde(rename = "decorationName")]
+    pub name: String,
+    pub priority: i32,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct AnimationConfig {
+    pub name: String,
+    /// Whether the config sets this animation, rather than inheriting it from its parent.
+    pub overridden: bool,
+    pub bezier: String,
+    pub enabled: bool,
+    pub speed: f64,
+    pub style: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct BezierCurve {
+    pub name: String,
+    #[serde(rename = "X0")]
+    x0: Option<f64>,
+    #[serde(rename = "Y0")]
+    y0: Option<f64>,
+    #[serde(rename = "X1")]
+    x1: Option<f64>,
+    #[serde(rename = "Y1")]
+    y1: Option<f64>,
+}
+
+impl BezierCurve {
+    /// The two control points of the curve, for hyprland versions that report them.
+    pub fn points(&self) -> Option<[(f64, f64); 2]> {
+        Some([(self.x0?, self.y0?), (self.x1?, self.y1?)])
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Bind {
+    pub modmask: ModMask,
+    pub submap: String,
+    pub key: String,
+    pub keycode: u32,
+    pub dispatcher: String,
+    pub arg: String,
+    pub description: String,
+    #[serde(flatten)]
+    pub flags: BindFlags,
+}
+
+/// The flags set on a bind, i.e. the letters after `bind` in its definition.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+pub struct BindFlags {
+    /// `l`: works while an input inhibitor (e.g. a lockscreen) is active
+    pub locked: bool,
+    /// `m`: a mouse bind
+    pub mouse: bool,
+    /// `r`: triggers on release
+    pub release: bool,
+    /// `e`: repeats while held
+    pub repeat: bool,
+    /// `n`: the key is also passed to the focused window
+    pub non_consuming: bool,
+    /// `t`: the keysym is transparent and does not cancel other binds
+    #[serde(default)]
+    pub catch_all: bool,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Version {
+    pub branch: String,
+    pub commit: String,
+    /// Only reported by hyprland 0.45 and later; see [`Version::number`].
+    #[serde(default)]
+    pub version: Option<String>,
+    pub dirty: bool,
+    pub commit_message: String,
+    pub commit_date: String,
+    /// `git describe` output, e.g. `v0.44.1-47-g0a1c7a5`.
+    pub tag: String,
+    pub flags: Vec<String>,
+}
+
+impl Version {
+    /// Returns the `(major, minor, patch)` release this build is at or after.
+    pub fn number(&self) -> Option<(u32, u32, u32)> {
+        let version = match &self.version {
+            Some(version) => version.as_str(),
+            None => self.tag.trim_start_matches('v').split('-').next()?,
+        };
+
+        let mut parts = version.split('.').map(|part| part.parse().ok());
+
+        Some((
+            parts.next()??,
+            parts.next()??,
+            parts.next().flatten().unwrap_or(0),
+        ))
+    }
+
+    pub fn at_least(&self, major: u32, minor: u32, patch: u32) -> bool {
+        self.number()
+            .is_some_and(|number| number >= (major, minor, patch))
+    }
+}
+
+/// The value of a config option, as reported by `getoption`.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OptionValue {
+    /// Also used for colors, which are reported as `0xAARRGGBB` integers.
+    Int(i64),
+    Float(f64),
+    #[serde(rename = "str")]
+    String(String),
+    Vec2(f64, f64),
+    /// Option types hyprland has no JSON representation for, such as gradients and CSS-style
+    /// gaps, in their config syntax (e.g. `"5 10 5 10"`).
+    Custom(String),
+}
+
+/// A `getoption` reply. The value is keyed by its type, next to the `option` and `set` fields.
+#[derive(Deserialize)]
+struct OptionReply {
+    #[serde(flatten)]
+    value: OptionValue,
+}
+
+/// The icon shown next to a notification sent with [`Command::notify`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Icon {
+    None,
+    Warning,
+    Info,
+    Hint,
+    Error,
+    Confused,
+    Ok,
+}
+
+impl Display for Icon {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let id = match self {
+            Icon::None => -1,
+            Icon::Warning => 0,
+            Icon::Info => 1,
+            Icon::Hint => 2,
+            Icon::Error => 3,
+            Icon::Confused => 4,
+            Icon::Ok => 5,
+        };
+
+        write!(f, "{id}")
+    }
+}
+
+/// An error message hyprland replied with instead of `ok`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HyprlandError(pub String);
+
+impl Display for HyprlandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for HyprlandError {}
+
+/// Reply buffers kept around between commands, so large replies (e.g. `j/clients` on busy
+/// sessions) don't have to grow a fresh allocation every time.
+static REPLY_BUFFERS: Mutex<Vec<Vec<u8>>> = Mutex::new(Vec::new());
+
+/// How many idle reply buffers to keep around at most.
+const MAX_REPLY_BUFFERS: usize = 4;
+
+/// A reply buffer taken from [`REPLY_BUFFERS`], returned to it on drop.
+struct ReplyBuffer(Vec<u8>);
+
+impl ReplyBuffer {
+    fn take() -> Self {
+        Self(REPLY_BUFFERS.lock().unwrap().pop().unwrap_or_default())
+    }
+}
+
+impl Deref for ReplyBuffer {
+    type Target = Vec<u8>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for ReplyBuffer {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl Drop for ReplyBuffer {
+    fn drop(&mut self) {
+        let mut buffer = std::mem::take(&mut self.0);
+        buffer.clear();
+
+        let mut buffers = REPLY_BUFFERS.lock().unwrap();
+        if buffers.len() < MAX_REPLY_BUFFERS {
+            buffers.push(buffer);
+        }
+    }
+}
+
 impl Command {
     pub async fn new() -> anyhow::Result<Self> {
         let path = hyprland_rundir()?.join(".socket.sock");
@@ -49,36 +452,712 @@ impl Command {
             .await
             .context("failed to connect to event stream")?;
 
-        Ok(Self { stream })
+        Ok(Self {
+            stream,
+            timeout: DEFAULT_TIMEOUT,
+        })
     }
 
-    async fn exec(mut self, command: &str) -> io::Result<Vec<u8>> {
-        self.stream.write_all(command.as_bytes()).await?;
-        self.stream.flush().await?;
+    /// Sets how long to wait for the reply before failing with [`io::ErrorKind::TimedOut`].
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
 
+    async fn exec(self, command: &str) -> io::Result<Vec<u8>> {
         let mut out = Vec::new();
-        self.stream.read_to_end(&mut out).await?;
+        self.exec_into(command, &mut out).await?;
 
         Ok(out)
     }
 
-    async fn json_vec<T: for<'de> Deserialize<'de>>(self, command: &str) -> io::Result<Vec<T>> {
-        let out = self.exec(command).await?;
+    async fn exec_into(mut self, command: &str, out: &mut Vec<u8>) -> io::Result<()> {
+        let timeout = self.timeout;
+        let exchange = async {
+            self.send(command).await?;
+
+            while self.read_chunk(out).await? != 0 {}
+
+            Ok(())
+        };
+
+        tokio::time::timeout(timeout, exchange)
+            .await
+            .map_err(|_| timed_out())?
+    }
+
+    /// Writes the request and closes the write half, so hyprland sees the complete request even
+    /// when it reads it in multiple parts.
+    async fn send(&mut self, command: &str) -> io::Result<()> {
+        self.stream.write_all(command.as_bytes()).await?;
+        self.stream.shutdown().await
+    }
+
+    /// Appends the next chunk of the reply to `out`, growing it as needed. Returns 0 at the end of
+    /// the reply.
+    async fn read_chunk(&mut self, out: &mut Vec<u8>) -> io::Result<usize> {
+        out.reserve(READ_CHUNK_SIZE);
+        self.stream.read_buf(out).await
+    }
+
+    async fn json<T: for<'de> Deserialize<'de>>(self, command: &str) -> io::Result<T> {
+        let mut out = ReplyBuffer::take();
+        self.exec_into(command, &mut out).await?;
 
         serde_json::from_slice(&out).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
     }
 
+    async fn text(self, command: &str) -> io::Result<String> {
+        let out = self.exec(command).await?;
+
+        String::from_utf8(out).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Runs a command that replies with `ok` on success, and with an error message otherwise.
+    async fn ok(self, command: &str) -> io::Result<Result<(), HyprlandError>> {
+        let reply = self.text(command).await?;
+
+        Ok(match reply.trim() {
+            "ok" => Ok(()),
+            error => Err(HyprlandError(error.to_owned())),
+        })
+    }
+
+    async fn json_vec<T: for<'de> Deserialize<'de>>(self, command: &str) -> io::Result<Vec<T>> {
+        self.json(command).await
+    }
+
+    /// Sends a request as-is and returns the reply, for requests without a typed wrapper. Queries
+    /// reply in plain text unless prefixed with `j/`, so e.g. `raw("clients")` gives the
+    /// human-readable form of [`Command::clients`].
+    pub async fn raw(self, request: &str) -> io::Result<String> {
+        self.text(request).await
+    }
+
     pub async fn workspaces(self) -> io::Result<Vec<Workspace>> {
         self.json_vec("j/workspaces").await
     }
 
+    pub async fn monitors(self) -> io::Result<Vec<Monitor>> {
+        self.json_vec("j/monitors").await
+    }
+
+    pub async fn devices(self) -> io::Result<Devices> {
+        self.json("j/devices").await
+    }
+
+    /// Returns the layer surfaces on each monitor, keyed by monitor name.
+    pub async fn layers(self) -> io::Result<BTreeMap<String, MonitorLayers>> {
+        self.json("j/layers").await
+    }
+
+    pub async fn binds(self) -> io::Result<Vec<Bind>> {
+        self.json_vec("j/binds").await
+    }
+
+    pub async fn version(self) -> io::Result<Version> {
+        self.json("j/version").await
+    }
+
+    /// Returns the human readable system report, as printed by `hyprctl systeminfo`.
+    pub async fn systeminfo(self) -> io::Result<String> {
+        self.text("systeminfo").await
+    }
+
+    pub async fn get_option(self, name: &str) -> io::Result<OptionValue> {
+        let reply: OptionReply = self.json(&format!("j/getoption {name}")).await?;
+
+        Ok(reply.value)
+    }
+
+    /// Sets a config option at runtime, like `hyprctl keyword`.
+    pub async fn keyword(
+        self,
+        name: &str,
+        value: impl Display,
+    ) -> io::Result<Result<(), HyprlandError>> {
+        self.ok(&format!("keyword {name} {value}")).await
+    }
+
+    /// Returns the cursor position in global layout coordinates.
+    pub async fn cursorpos(self) -> io::Result<(i32, i32)> {
+        #[derive(Deserialize)]
+        struct Position {
+            x: i32,
+            y: i32,
+        }
+
+        let Position { x, y } = self.json("j/cursorpos").await?;
+
+        Ok((x, y))
+    }
+
+    pub async fn workspacerules(self) -> io::Result<Vec<WorkspaceRule>> {
+        self.json_vec("j/workspacerules").await
+    }
+
+    /// Returns the errors hyprland found in its config on the last (re)load.
+    pub async fn configerrors(self) -> io::Result<Vec<String>> {
+        let errors: Vec<String> = self.json_vec("j/configerrors").await?;
+
+        // hyprland reports a single empty string when there are no errors
+        Ok(errors.into_iter().filter(|e| !e.is_empty()).collect())
+    }
+
+    /// Shows a notification in hyprland's own on-screen notification area. `color` is an RGB
+    /// value; `None` uses the icon's default color.
+    pub async fn notify(
+        self,
+        icon: Icon,
+        duration: Duration,
+        color: Option<u32>,
+        message: &str,
+    ) -> io::Result<Result<(), HyprlandError>> {
+        let time = duration.as_millis();
+        let color = match color {
+            Some(rgb) => format!("rgb({rgb:06x})"),
+            None => String::from("0"),
+        };
+
+        self.ok(&format!("notify {icon} {time} {color} {message}"))
+            .await
+    }
+
+    /// Dismisses the oldest `amount` notifications, or all of them when `None`.
+    pub async fn dismissnotify(self, amount: Option<u32>) -> io::Result<Result<(), HyprlandError>> {
+        match amount {
+            Some(amount) => self.ok(&format!("dismissnotify {amount}")).await,
+            None => self.ok("dismissnotify").await,
+        }
+    }
+
+    /// Reloads the hyprland config.
+    pub async fn reload(self) -> io::Result<Result<(), HyprlandError>> {
+        self.ok("reload").await
+    }
+
+    /// Enters kill mode, where the next clicked window is killed.
+    pub async fn kill_mode(self) -> io::Result<Result<(), HyprlandError>> {
+        self.ok("kill").await
+    }
+
+    pub async fn set_cursor(self, theme: &str, size: u32) -> io::Result<Result<(), HyprlandError>> {
+        self.ok(&format!("setcursor {theme} {size}")).await
+    }
+
+    /// Creates a headless (virtual) output. Hyprland only replies `ok`, so the output is created
+    /// under the given name rather than an auto-generated one.
+    pub async fn create_headless_output(self, name: &str) -> io::Result<Result<(), HyprlandError>> {
+        self.ok(&format!("output create headless {name}")).await
+    }
+
+    /// Removes an output created with [`Command::create_headless_output`].
+    pub async fn remove_output(self, name: &str) -> io::Result<Result<(), HyprlandError>> {
+        self.ok(&format!("output remove {name}")).await
+    }
+
+    pub async fn globalshortcuts(self) -> io::Result<Vec<GlobalShortcut>> {
+        self.json_vec("j/globalshortcuts").await
+    }
+
+    /// Returns the decorations (borders, shadows, group bars, ...) of a window.
+    pub async fn decorations(self, address: WindowAddress) -> io::Result<Vec<Decoration>> {
+        self.json_vec(&format!("j/decorations address:{address}"))
+            .await
+    }
+
+    pub async fn animations(self) -> io::Result<(Vec<AnimationConfig>, Vec<BezierCurve>)> {
+        self.json("j/animations").await
+    }
+
     pub async fn clients(self) -> io::Result<Vec<Client>> {
         self.json_vec("j/clients").await
     }
 
+    /// Like [`Command::clients`], but yields each client as soon as it has been received instead
+    /// of waiting for the whole reply. Stops after the first error.
+    pub async gen fn clients_stream(mut self) -> io::Result<Client> {
+        if let Err(e) = self.send("j/clients").await {
+            yield Err(e);
+            return;
+        }
+
+        let mut buf = Vec::new();
+        let mut splitter = ArraySplitter::default();
+
+        loop {
+            let read = match tokio::time::timeout(self.timeout, self.read_chunk(&mut buf)).await {
+                Ok(Ok(read)) => read,
+                Ok(Err(e)) => {
+                    yield Err(e);
+                    return;
+                }
+                Err(_) => {
+                    yield Err(timed_out());
+                    return;
+                }
+            };
+
+            if read == 0 {
+                break;
+            }
+
+            while let Some(element) = splitter.next_element(&buf) {
+                match serde_json::from_slice(&buf[element]) {
+                    Ok(client) => yield Ok(client),
+                    Err(e) => {
+                        yield Err(io::Error::new(io::ErrorKind::InvalidData, e));
+                        return;
+                    }
+                }
+            }
+
+            // drop the part of the reply that has been deserialized already
+            let consumed = splitter.consumed();
+            buf.drain(..consumed);
+            splitter.rebase(consumed);
+        }
+
+        if !splitter.finished() {
+            yield Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "incomplete clients reply",
+            ));
+        }
+    }
+
+    pub async fn client(self, address: WindowAddress) -> io::Result<Option<Client>> {
+        #[derive(Deserialize)]
+        struct Address {
+            address: WindowAddress,
+        }
+
+        let mut out = ReplyBuffer::take();
+        self.exec_into("j/clients", &mut out).await?;
+
+        // only read the address of the other clients, instead of deserializing all of them
+        let mut splitter = ArraySplitter::default();
+        while let Some(element) = splitter.next_element(&out) {
+            let element = &out[element];
+
+            let found: Address = serde_json::from_slice(element)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+            if found.address == address {
+                return serde_json::from_slice(element)
+                    .map(Some)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Returns the focused window, or `None` when no window is focused.
+    pub async fn activewindow(self) -> io::Result<Option<Client>> {
+        let reply = self.json("j/activewindow").await?;
+
+        parse_activewindow(reply).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
     pub async fn dispatch(self, dispatcher: Dispatcher) -> io::Result<()> {
         self.exec(&format!("j/dispatch {dispatcher}")).await?;
 
         Ok(())
     }
+
+    /// Sends all commands in the batch over this connection, returning the outcome of each.
+    pub async fn batch(self, batch: CommandBatch) -> io::Result<Vec<Result<(), HyprlandError>>> {
+        let reply = self
+            .text(&format!("[[BATCH]]{}", batch.commands.join(";")))
+            .await?;
+
+        Ok(split_batch_reply(&reply)
+            .map(|reply| match reply.trim() {
+                "ok" => Ok(()),
+                error => Err(HyprlandError(error.to_owned())),
+            })
+            .collect())
+    }
+}
+
+/// Hyprland replies with an empty object when nothing is focused. Anything else must be a client,
+/// so that a client that fails to parse is reported instead of looking like no focused window.
+fn parse_activewindow(
+    reply: serde_json::Map<String, serde_json::Value>,
+) -> serde_json::Result<Option<Client>> {
+    if reply.is_empty() {
+        return Ok(None);
+    }
+
+    serde_json::from_value(serde_json::Value::Object(reply)).map(Some)
+}
+
+/// How much room to make in the reply buffer before each read.
+const READ_CHUNK_SIZE: usize = 16 * 1024;
+
+fn timed_out() -> io::Error {
+    io::Error::new(io::ErrorKind::TimedOut, "hyprland did not reply in time")
+}
+
+/// Finds the elements of a JSON array that arrives in chunks, so they can be deserialized before
+/// the rest of the array has been received. Positions refer to the buffer the chunks are appended
+/// to, minus whatever was removed from its front with [`ArraySplitter::rebase`].
+#[derive(Default)]
+struct ArraySplitter {
+    /// Where to continue scanning.
+    pos: usize,
+    /// Start of the element currently being scanned, if any.
+    start: Option<usize>,
+    depth: u32,
+    in_string: bool,
+    escaped: bool,
+    finished: bool,
+}
+
+impl ArraySplitter {
+    /// Returns the range of the next complete element in `buf`, if it has been received yet.
+    fn next_element(&mut self, buf: &[u8]) -> Option<Range<usize>> {
+        while self.pos < buf.len() && !self.finished {
+            let i = self.pos;
+            self.pos += 1;
+
+            if self.in_string {
+                match buf[i] {
+                    _ if self.escaped => self.escaped = false,
+                    b'\\' => self.escaped = true,
+                    b'"' => self.in_string = false,
+                    _ => {}
+                }
+
+                continue;
+            }
+
+            match buf[i] {
+                b'[' | b'{' => {
+                    self.begin_element(i);
+                    self.depth += 1;
+                }
+                b']' | b'}' => {
+                    self.depth = self.depth.saturating_sub(1);
+
+                    if self.depth == 0 {
+                        self.finished = true;
+                        return self.start.take().map(|start| start..i);
+                    }
+                }
+                b',' if self.depth == 1 => {
+                    return self.start.take().map(|start| start..i);
+                }
+                c if c.is_ascii_whitespace() => {}
+                c => {
+                    self.begin_element(i);
+                    self.in_string = c == b'"';
+                }
+            }
+        }
+
+        None
+    }
+
+    fn begin_element(&mut self, i: usize) {
+        if self.depth == 1 && self.start.is_none() {
+            self.start = Some(i);
+        }
+    }
+
+    /// How many bytes at the front of the buffer are no longer needed.
+    fn consumed(&self) -> usize {
+        self.start.unwrap_or(self.pos)
+    }
+
+    /// Adjusts positions after `n` bytes were removed from the front of the buffer.
+    fn rebase(&mut self, n: usize) {
+        self.pos -= n;
+        self.start = self.start.map(|start| start - n);
+    }
+
+    fn finished(&self) -> bool {
+        self.finished
+    }
+}
+
+/// Several commands to be sent in one request, using hyprland's `[[BATCH]]` syntax.
+///
+/// Commands are separated by `;`, so arguments must not contain one outside of `[...]` rules.
+#[derive(Clone, Debug, Default)]
+pub struct CommandBatch {
+    commands: Vec<String>,
+}
+
+impl CommandBatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn dispatch(mut self, dispatcher: Dispatcher) -> Self {
+        self.commands.push(format!("dispatch {dispatcher}"));
+        self
+    }
+
+    pub fn keyword(mut self, name: &str, value: impl Display) -> Self {
+        self.commands.push(format!("keyword {name} {value}"));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+}
+
+/// Splits the concatenated reply to a batch request into the replies to each command.
+fn split_batch_reply(reply: &str) -> impl Iterator<Item = &str> {
+    reply
+        .strip_suffix("\n\n\n")
+        .unwrap_or(reply)
+        .split("\n\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn client() {
+        let client: Client = serde_json::from_str(
+            r#"{
+                "address": "0x55d0c8a0e2f0",
+                "mapped": true,
+                "hidden": false,
+                "at": [10, 40],
+                "size": [1900, 1030],
+                "workspace": { "id": 2, "name": "2" },
+                "floating": false,
+                "pseudo": false,
+                "monitor": 0,
+                "class": "kitty",
+                "title": "~",
+                "initialClass": "kitty",
+                "initialTitle": "kitty",
+                "pid": 1234,
+                "xwayland": false,
+                "pinned": false,
+                "fullscreen": 2,
+                "fullscreenClient": 0,
+                "grouped": [],
+                "tags": [],
+                "swallowing": "0x0",
+                "focusHistoryID": 1
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(client.address, WindowAddress(0x55d0c8a0e2f0));
+        assert_eq!(client.size, (1900, 1030));
+        assert_eq!(client.workspace.id, WorkspaceId(2));
+        assert_eq!(client.class.as_str(), "kitty");
+        assert_eq!(client.fullscreen, FullscreenState::Fullscreen);
+        assert_eq!(client.fullscreen_client, FullscreenState::None);
+        assert_eq!(client.focus_history_id, 1);
+    }
+
+    #[test]
+    fn layers() {
+        let layers: BTreeMap<String, MonitorLayers> = serde_json::from_str(
+            r#"{
+                "DP-1": {
+                    "levels": {
+                        "0": [
+                            {
+                                "address": "0x55d0c8b3a6e0",
+                                "x": 0,
+                                "y": 0,
+                                "w": 2560,
+                                "h": 1440,
+                                "namespace": "hyprpaper",
+                                "pid": 1021
+                            }
+                        ],
+                        "1": [],
+                        "2": [],
+                        "3": []
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let levels = &layers["DP-1"].levels;
+        assert_eq!(levels.len(), 4);
+        assert_eq!(levels[&LayerLevel::Background][0].namespace, "hyprpaper");
+        assert!(levels[&LayerLevel::Overlay].is_empty());
+    }
+
+    #[test]
+    fn binds() {
+        let binds: Vec<Bind> = serde_json::from_str(
+            r#"[{
+                "locked": false,
+                "mouse": false,
+                "release": false,
+                "repeat": true,
+                "longPress": false,
+                "non_consuming": false,
+                "has_description": false,
+                "modmask": 65,
+                "submap": "",
+                "key": "L",
+                "keycode": 0,
+                "catch_all": false,
+                "description": "",
+                "dispatcher": "resizeactive",
+                "arg": "10 0"
+            }]"#,
+        )
+        .unwrap();
+
+        assert_eq!(binds[0].modmask, ModMask::SUPER | ModMask::SHIFT);
+        assert_eq!(binds[0].modmask.to_string(), "SHIFT SUPER");
+        assert_eq!(binds[0].dispatcher, "resizeactive");
+        assert!(binds[0].flags.repeat);
+    }
+
+    #[test]
+    fn version() {
+        let mut version: Version = serde_json::from_str(
+            r#"{
+                "branch": "main",
+                "commit": "0a1c7a5c8e5b0a5fe9c1a7c1e1b8bd3e60a3c5d2",
+                "dirty": false,
+                "commit_message": "core: fix something",
+                "commit_date": "Fri Oct 4 12:00:00 2024",
+                "tag": "v0.44.1-47-g0a1c7a5",
+                "commits": "5210",
+                "buildAquamarine": "0.4.2",
+                "flags": []
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(version.number(), Some((0, 44, 1)));
+        assert!(version.at_least(0, 44, 0));
+        assert!(!version.at_least(0, 45, 0));
+
+        version.version = Some(String::from("0.45.0"));
+        assert_eq!(version.number(), Some((0, 45, 0)));
+    }
+
+    #[test]
+    fn batch_reply() {
+        let replies = split_batch_reply("ok\n\n\nInvalid dispatcher\n\n\nok\n\n\n");
+        assert_eq!(
+            replies.collect::<Vec<_>>(),
+            ["ok", "Invalid dispatcher", "ok"]
+        );
+    }
+
+    #[test]
+    fn animations() {
+        let (animations, beziers): (Vec<AnimationConfig>, Vec<BezierCurve>) = serde_json::from_str(
+            r#"[
+                    [
+                        {
+                            "name": "global",
+                            "overridden": true,
+                            "bezier": "default",
+                            "enabled": true,
+                            "speed": 10.00,
+                            "style": ""
+                        },
+                        {
+                            "name": "workspaces",
+                            "overridden": true,
+                            "bezier": "snappy",
+                            "enabled": true,
+                            "speed": 4.00,
+                            "style": "slide"
+                        }
+                    ],
+                    [
+                        { "name": "default" },
+                        { "name": "snappy", "X0": 0.05, "Y0": 0.9, "X1": 0.1, "Y1": 1.05 }
+                    ]
+                ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(animations[1].style, "slide");
+        assert_eq!(beziers[0].points(), None);
+        assert_eq!(beziers[1].points(), Some([(0.05, 0.9), (0.1, 1.05)]));
+    }
+
+    #[test]
+    fn array_splitter() {
+        let reply = br#"[{"title": "a, [b] \"c\" {d}", "n": [1, 2]}, {"title": "e"}, 3]"#;
+
+        // feed the reply in small chunks, like it would arrive from the socket
+        let mut buf = Vec::new();
+        let mut splitter = ArraySplitter::default();
+        let mut elements = Vec::new();
+
+        for chunk in reply.chunks(5) {
+            buf.extend_from_slice(chunk);
+
+            while let Some(element) = splitter.next_element(&buf) {
+                elements.push(String::from_utf8(buf[element].to_vec()).unwrap());
+            }
+
+            let consumed = splitter.consumed();
+            buf.drain(..consumed);
+            splitter.rebase(consumed);
+        }
+
+        assert!(splitter.finished());
+        assert_eq!(
+            elements,
+            [
+                r#"{"title": "a, [b] \"c\" {d}", "n": [1, 2]}"#,
+                r#"{"title": "e"}"#,
+                "3",
+            ]
+        );
+    }
+
+    #[test]
+    fn array_splitter_empty() {
+        let mut splitter = ArraySplitter::default();
+        assert_eq!(splitter.next_element(b" [ ] "), None);
+        assert!(splitter.finished());
+    }
+
+    #[test]
+    fn activewindow() {
+        let reply = |json| parse_activewindow(serde_json::from_str(json).unwrap());
+
+        assert!(reply("{}").unwrap().is_none());
+
+        // a client that doesn't parse is an error, not "no focused window"
+        assert!(reply(r#"{ "address": "0x55d0c8a0e2f0", "fullscreen": true }"#).is_err());
+    }
+
+    #[test]
+    fn get_option() {
+        let reply: OptionReply = serde_json::from_str(
+            r#"{ "option": "general:gaps_out", "custom": "5 10 5 10", "set": true }"#,
+        )
+        .unwrap();
+        assert_eq!(reply.value, OptionValue::Custom("5 10 5 10".into()));
+
+        let reply: OptionReply = serde_json::from_str(
+            r#"{ "option": "decoration:shadow:offset", "vec2": [2.5, -4], "set": false }"#,
+        )
+        .unwrap();
+        assert_eq!(reply.value, OptionValue::Vec2(2.5, -4.0));
+
+        let reply: OptionReply = serde_json::from_str(
+            r#"{ "option": "general:col.active_border", "int": 4294967295, "set": true }"#,
+        )
+        .unwrap();
+        assert_eq!(reply.value, OptionValue::Int(0xffffffff));
+    }
 }
@@ -1,9 +1,163 @@
 use std::fmt::Display;
 
-use super::WorkspaceId;
+use super::{ModMask, WindowAddress, WorkspaceId};
 
 pub enum Dispatcher {
     ChangeWorkspace(WorkspaceSpec),
+    RenameWorkspace(WorkspaceId, String),
+    KillActive,
+    CloseWindow(WindowSpec),
+    /// Acts on the active window if no window is given.
+    ToggleFloating(Option<WindowSpec>),
+    /// Acts on the active window if no window is given.
+    Pin(Option<WindowSpec>),
+    CenterWindow,
+    Exec {
+        rules: Vec<ExecRule>,
+        command: String,
+    },
+    /// Runs a raw shell command, without support for rules.
+    Execr(String),
+    MoveFocus(Direction),
+    MoveWindowDir(MoveWindowTarget),
+    FocusWindow(WindowSpec),
+    FocusMonitor(MonitorSpec),
+    ToggleGroup,
+    ChangeGroupActive(GroupActive),
+    MoveIntoGroup(Direction),
+    MoveOutOfGroup,
+    LockActiveGroup(LockAction),
+    LockGroups(LockAction),
+    ResizeActive(ResizeParams),
+    MoveActive(ResizeParams),
+    ResizeWindowPixel(ResizeParams, WindowSpec),
+    SwapWindow(Direction),
+    SwapActiveWorkspaces(MonitorSpec, MonitorSpec),
+    /// Focuses the next window on the workspace, or the previous one if `prev` is set.
+    CycleNext {
+        prev: bool,
+    },
+    FocusUrgentOrLast,
+    FocusCurrentOrLast,
+    /// Passes the key that triggered the bind on to a window.
+    Pass(WindowSpec),
+    /// Sends a key combination to a window, or the active window if none is given.
+    SendShortcut {
+        mods: ModMask,
+        key: String,
+        window: Option<WindowSpec>,
+    },
+    /// Turns monitors on or off, or all of them if no monitor is given.
+    Dpms {
+        state: DpmsState,
+        monitor: Option<String>,
+    },
+    /// Enters a submap, or goes back to the global keybinds if none is given.
+    Submap(Option<String>),
+    /// Warps the cursor to a position in the global layout.
+    MoveCursor {
+        x: i32,
+        y: i32,
+    },
+    /// Warps the cursor to a corner of the active window.
+    MoveCursorToCorner(Corner),
+    /// Toggles an option for the active workspace.
+    WorkspaceOpt(WorkspaceOption),
+    /// Dwindle layout only.
+    ToggleSplit,
+    /// Dwindle layout only.
+    SwapSplit,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WorkspaceOption {
+    AllFloat,
+    AllPseudo,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Corner {
+    BottomLeft,
+    BottomRight,
+    TopRight,
+    TopLeft,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DpmsState {
+    On,
+    Off,
+    Toggle,
+}
+
+/// A size or position change, either relative to the window's current geometry or exact.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ResizeParams {
+    pub exact: bool,
+    pub x: Length,
+    pub y: Length,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Length {
+    Pixels(i32),
+    /// Percentage of the monitor's size.
+    Percent(f32),
+}
+
+/// Which window of a group to make active.
+pub enum GroupActive {
+    Forward,
+    Back,
+    /// Position in the group, starting at 1.
+    Index(u32),
+}
+
+pub enum LockAction {
+    Lock,
+    Unlock,
+    Toggle,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// Where [`Dispatcher::MoveWindowDir`] moves the active window to.
+pub enum MoveWindowTarget {
+    Direction(Direction),
+    Monitor(MonitorSpec),
+}
+
+pub enum MonitorSpec {
+    /// The monitor next to the focused one in a direction.
+    Direction(Direction),
+    /// A monitor relative to the focused one, by id.
+    Relative(i32),
+    Id(i32),
+    Name(String),
+    Current,
+}
+
+/// A window rule applied to the windows spawned by [`Dispatcher::Exec`].
+pub enum ExecRule {
+    Workspace {
+        workspace: WorkspaceSpec,
+        silent: bool,
+    },
+    Monitor(String),
+    Float,
+    Tile,
+    Fullscreen,
+    Maximize,
+    Pin,
+    NoInitialFocus,
+    /// Any other rule, written as in hyprland's config.
+    Other(String),
 }
 
 pub enum WorkspaceSpec {
@@ -22,10 +176,235 @@ pub enum WorkspaceSpec {
     Special(Option<String>),
 }
 
+/// Selects a window for dispatchers that act on one. Class and title patterns are regexes.
+pub enum WindowSpec {
+    Class(String),
+    InitialClass(String),
+    Title(String),
+    InitialTitle(String),
+    Pid(u32),
+    Address(WindowAddress),
+    ActiveWindow,
+    Floating,
+    Tiled,
+}
+
 impl Display for Dispatcher {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Dispatcher::ChangeWorkspace(spec) => write!(f, "workspace {}", spec),
+            Dispatcher::RenameWorkspace(id, name) => write!(f, "renameworkspace {id} {name}"),
+            Dispatcher::KillActive => write!(f, "killactive"),
+            Dispatcher::CloseWindow(window) => write!(f, "closewindow {window}"),
+            Dispatcher::ToggleFloating(None) => write!(f, "togglefloating"),
+            Dispatcher::ToggleFloating(Some(window)) => write!(f, "togglefloating {window}"),
+            Dispatcher::Pin(None) => write!(f, "pin"),
+            Dispatcher::Pin(Some(window)) => write!(f, "pin {window}"),
+            Dispatcher::CenterWindow => write!(f, "centerwindow"),
+            Dispatcher::Exec { rules, command } => {
+                write!(f, "exec ")?;
+
+                if !rules.is_empty() {
+                    write!(f, "[")?;
+                    for (i, rule) in rules.iter().enumerate() {
+                        if i > 0 {
+                            write!(f, "; ")?;
+                        }
+                        write!(f, "{rule}")?;
+                    }
+                    write!(f, "] ")?;
+                }
+
+                write!(f, "{command}")
+            }
+            Dispatcher::Execr(command) => write!(f, "execr {command}"),
+            Dispatcher::MoveFocus(direction) => write!(f, "movefocus {direction}"),
+            Dispatcher::MoveWindowDir(target) => write!(f, "movewindow {target}"),
+            Dispatcher::FocusWindow(window) => write!(f, "focuswindow {window}"),
+            Dispatcher::FocusMonitor(monitor) => write!(f, "focusmonitor {monitor}"),
+            Dispatcher::ToggleGroup => write!(f, "togglegroup"),
+            Dispatcher::ChangeGroupActive(active) => write!(f, "changegroupactive {active}"),
+            Dispatcher::MoveIntoGroup(direction) => write!(f, "moveintogroup {direction}"),
+            Dispatcher::MoveOutOfGroup => write!(f, "moveoutofgroup"),
+            Dispatcher::LockActiveGroup(action) => write!(f, "lockactivegroup {action}"),
+            Dispatcher::LockGroups(action) => write!(f, "lockgroups {action}"),
+            Dispatcher::ResizeActive(params) => write!(f, "resizeactive {params}"),
+            Dispatcher::MoveActive(params) => write!(f, "moveactive {params}"),
+            Dispatcher::ResizeWindowPixel(params, window) => {
+                write!(f, "resizewindowpixel {params},{window}")
+            }
+            Dispatcher::SwapWindow(direction) => write!(f, "swapwindow {direction}"),
+            Dispatcher::SwapActiveWorkspaces(a, b) => write!(f, "swapactiveworkspaces {a} {b}"),
+            Dispatcher::CycleNext { prev: false } => write!(f, "cyclenext"),
+            Dispatcher::CycleNext { prev: true } => write!(f, "cyclenext prev"),
+            Dispatcher::FocusUrgentOrLast => write!(f, "focusurgentorlast"),
+            Dispatcher::FocusCurrentOrLast => write!(f, "focuscurrentorlast"),
+            Dispatcher::Pass(window) => write!(f, "pass {window}"),
+            Dispatcher::SendShortcut {
+                mods,
+                key,
+                window: None,
+            } => write!(f, "sendshortcut {mods}, {key}"),
+            Dispatcher::SendShortcut {
+                mods,
+                key,
+                window: Some(window),
+            } => write!(f, "sendshortcut {mods}, {key}, {window}"),
+            Dispatcher::Dpms {
+                state,
+                monitor: None,
+            } => write!(f, "dpms {state}"),
+            Dispatcher::Dpms {
+                state,
+                monitor: Some(monitor),
+            } => write!(f, "dpms {state} {monitor}"),
+            Dispatcher::Submap(None) => write!(f, "submap reset"),
+            Dispatcher::Submap(Some(name)) => write!(f, "submap {name}"),
+            Dispatcher::MoveCursor { x, y } => write!(f, "movecursor {x} {y}"),
+            Dispatcher::MoveCursorToCorner(corner) => write!(f, "movecursortocorner {corner}"),
+            Dispatcher::WorkspaceOpt(option) => write!(f, "workspaceopt {option}"),
+            Dispatcher::ToggleSplit => write!(f, "togglesplit"),
+            Dispatcher::SwapSplit => write!(f, "swapsplit"),
+        }
+    }
+}
+
+impl Display for DpmsState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DpmsState::On => write!(f, "on"),
+            DpmsState::Off => write!(f, "off"),
+            DpmsState::Toggle => write!(f, "toggle"),
+        }
+    }
+}
+
+impl Display for WorkspaceOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WorkspaceOption::AllFloat => write!(f, "allfloat"),
+            WorkspaceOption::AllPseudo => write!(f, "allpseudo"),
+        }
+    }
+}
+
+impl Display for Corner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Corner::BottomLeft => write!(f, "0"),
+            Corner::BottomRight => write!(f, "1"),
+            Corner::TopRight => write!(f, "2"),
+            Corner::TopLeft => write!(f, "3"),
+        }
+    }
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Direction::Left => write!(f, "l"),
+            Direction::Right => write!(f, "r"),
+            Direction::Up => write!(f, "u"),
+            Direction::Down => write!(f, "d"),
+        }
+    }
+}
+
+impl Display for MoveWindowTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MoveWindowTarget::Direction(direction) => write!(f, "{direction}"),
+            MoveWindowTarget::Monitor(monitor) => write!(f, "mon:{monitor}"),
+        }
+    }
+}
+
+impl ResizeParams {
+    pub fn exact(x: Length, y: Length) -> Self {
+        Self { exact: true, x, y }
+    }
+
+    pub fn relative(x: Length, y: Length) -> Self {
+        Self { exact: false, x, y }
+    }
+}
+
+impl Display for ResizeParams {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.exact {
+            write!(f, "exact ")?;
+        }
+
+        write!(f, "{} {}", self.x, self.y)
+    }
+}
+
+impl Display for Length {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Length::Pixels(pixels) => write!(f, "{pixels}"),
+            Length::Percent(percent) => write!(f, "{percent}%"),
+        }
+    }
+}
+
+impl Display for GroupActive {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GroupActive::Forward => write!(f, "f"),
+            GroupActive::Back => write!(f, "b"),
+            GroupActive::Index(index) => write!(f, "{index}"),
+        }
+    }
+}
+
+impl Display for LockAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LockAction::Lock => write!(f, "lock"),
+            LockAction::Unlock => write!(f, "unlock"),
+            LockAction::Toggle => write!(f, "toggle"),
+        }
+    }
+}
+
+impl Display for MonitorSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MonitorSpec::Direction(direction) => write!(f, "{direction}"),
+            MonitorSpec::Relative(id) => write!(f, "{id:+}"),
+            MonitorSpec::Id(id) => write!(f, "{id}"),
+            MonitorSpec::Name(name) => write!(f, "{name}"),
+            MonitorSpec::Current => write!(f, "current"),
+        }
+    }
+}
+
+impl From<Direction> for MoveWindowTarget {
+    fn from(direction: Direction) -> Self {
+        Self::Direction(direction)
+    }
+}
+
+impl Display for ExecRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExecRule::Workspace {
+                workspace,
+                silent: false,
+            } => write!(f, "workspace {workspace}"),
+            ExecRule::Workspace {
+                workspace,
+                silent: true,
+            } => write!(f, "workspace {workspace} silent"),
+            ExecRule::Monitor(monitor) => write!(f, "monitor {monitor}"),
+            ExecRule::Float => write!(f, "float"),
+            ExecRule::Tile => write!(f, "tile"),
+            ExecRule::Fullscreen => write!(f, "fullscreen"),
+            ExecRule::Maximize => write!(f, "maximize"),
+            ExecRule::Pin => write!(f, "pin"),
+            ExecRule::NoInitialFocus => write!(f, "noinitialfocus"),
+            ExecRule::Other(rule) => write!(f, "{rule}"),
         }
     }
 }
@@ -33,7 +412,7 @@ impl Display for Dispatcher {
 impl Display for WorkspaceSpec {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            WorkspaceSpec::Id(id) => write!(f, "{}", id.0),
+            WorkspaceSpec::Id(id) => write!(f, "{id}"),
             WorkspaceSpec::RelativeId(id) => write!(f, "{id:+}"),
             WorkspaceSpec::MonitorRelativeId(id) => write!(f, "m{id:+}"),
             WorkspaceSpec::MonitorAbsoluteId(id) => write!(f, "m~{id}"),
@@ -66,6 +445,28 @@ impl Display for WorkspaceSpec {
     }
 }
 
+impl Display for WindowSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WindowSpec::Class(class) => write!(f, "class:{class}"),
+            WindowSpec::InitialClass(class) => write!(f, "initialclass:{class}"),
+            WindowSpec::Title(title) => write!(f, "title:{title}"),
+            WindowSpec::InitialTitle(title) => write!(f, "initialtitle:{title}"),
+            WindowSpec::Pid(pid) => write!(f, "pid:{pid}"),
+            WindowSpec::Address(address) => write!(f, "address:{address}"),
+            WindowSpec::ActiveWindow => write!(f, "activewindow"),
+            WindowSpec::Floating => write!(f, "floating"),
+            WindowSpec::Tiled => write!(f, "tiled"),
+        }
+    }
+}
+
+impl From<WindowAddress> for WindowSpec {
+    fn from(address: WindowAddress) -> Self {
+        Self::Address(address)
+    }
+}
+
 impl From<WorkspaceId> for WorkspaceSpec {
     fn from(id: WorkspaceId) -> Self {
         Self::Id(id)
@@ -84,4 +485,185 @@ mod tests {
         assert_eq!(WorkspaceSpec::MonitorRelativeId(-1).to_string(), "m-1");
         assert_eq!(WorkspaceSpec::MonitorAbsoluteId(1).to_string(), "m~1");
     }
+
+    #[test]
+    fn window_spec() {
+        assert_eq!(
+            WindowSpec::Class("^(kitty)$".into()).to_string(),
+            "class:^(kitty)$"
+        );
+        assert_eq!(
+            WindowSpec::InitialTitle("vim".into()).to_string(),
+            "initialtitle:vim"
+        );
+        assert_eq!(WindowSpec::Pid(1234).to_string(), "pid:1234");
+        assert_eq!(
+            WindowSpec::Address(WindowAddress(0x55d0c8a0e2f0)).to_string(),
+            "address:0x55d0c8a0e2f0"
+        );
+        assert_eq!(WindowSpec::ActiveWindow.to_string(), "activewindow");
+    }
+
+    #[test]
+    fn dispatcher() {
+        assert_eq!(
+            Dispatcher::RenameWorkspace(WorkspaceId(3), "mail".into()).to_string(),
+            "renameworkspace 3 mail"
+        );
+        assert_eq!(Dispatcher::KillActive.to_string(), "killactive");
+        assert_eq!(
+            Dispatcher::CloseWindow(WindowSpec::Class("firefox".into())).to_string(),
+            "closewindow class:firefox"
+        );
+        assert_eq!(
+            Dispatcher::ToggleFloating(None).to_string(),
+            "togglefloating"
+        );
+        assert_eq!(
+            Dispatcher::Pin(Some(WindowSpec::Pid(42))).to_string(),
+            "pin pid:42"
+        );
+        assert_eq!(
+            Dispatcher::Exec {
+                rules: vec![],
+                command: "kitty".into()
+            }
+            .to_string(),
+            "exec kitty"
+        );
+        assert_eq!(
+            Dispatcher::Exec {
+                rules: vec![
+                    ExecRule::Workspace {
+                        workspace: WorkspaceSpec::Id(WorkspaceId(2)),
+                        silent: true
+                    },
+                    ExecRule::Float,
+                ],
+                command: "kitty".into()
+            }
+            .to_string(),
+            "exec [workspace 2 silent; float] kitty"
+        );
+        assert_eq!(
+            Dispatcher::MoveFocus(Direction::Left).to_string(),
+            "movefocus l"
+        );
+        assert_eq!(
+            Dispatcher::MoveWindowDir(Direction::Up.into()).to_string(),
+            "movewindow u"
+        );
+        assert_eq!(
+            Dispatcher::MoveWindowDir(MoveWindowTarget::Monitor(MonitorSpec::Name("DP-1".into())))
+                .to_string(),
+            "movewindow mon:DP-1"
+        );
+        assert_eq!(
+            Dispatcher::FocusWindow(WindowAddress(0x1a2b).into()).to_string(),
+            "focuswindow address:0x1a2b"
+        );
+        assert_eq!(
+            Dispatcher::FocusMonitor(MonitorSpec::Relative(-1)).to_string(),
+            "focusmonitor -1"
+        );
+        assert_eq!(
+            Dispatcher::ChangeGroupActive(GroupActive::Index(2)).to_string(),
+            "changegroupactive 2"
+        );
+        assert_eq!(
+            Dispatcher::ChangeGroupActive(GroupActive::Back).to_string(),
+            "changegroupactive b"
+        );
+        assert_eq!(
+            Dispatcher::LockGroups(LockAction::Toggle).to_string(),
+            "lockgroups toggle"
+        );
+        assert_eq!(
+            Dispatcher::SwapWindow(Direction::Right).to_string(),
+            "swapwindow r"
+        );
+        assert_eq!(
+            Dispatcher::SwapActiveWorkspaces(
+                MonitorSpec::Name("DP-1".into()),
+                MonitorSpec::Name("HDMI-A-1".into())
+            )
+            .to_string(),
+            "swapactiveworkspaces DP-1 HDMI-A-1"
+        );
+        assert_eq!(
+            Dispatcher::CycleNext { prev: true }.to_string(),
+            "cyclenext prev"
+        );
+        assert_eq!(
+            Dispatcher::FocusUrgentOrLast.to_string(),
+            "focusurgentorlast"
+        );
+        assert_eq!(
+            Dispatcher::SendShortcut {
+                mods: ModMask::default(),
+                key: "XF86AudioPlay".into(),
+                window: Some(WindowSpec::Class("spotify".into())),
+            }
+            .to_string(),
+            "sendshortcut , XF86AudioPlay, class:spotify"
+        );
+        assert_eq!(
+            Dispatcher::SendShortcut {
+                mods: ModMask::SUPER | ModMask::SHIFT,
+                key: "F".into(),
+                window: None,
+            }
+            .to_string(),
+            "sendshortcut SHIFT SUPER, F"
+        );
+        assert_eq!(
+            Dispatcher::Dpms {
+                state: DpmsState::Off,
+                monitor: Some("DP-1".into()),
+            }
+            .to_string(),
+            "dpms off DP-1"
+        );
+        assert_eq!(Dispatcher::Submap(None).to_string(), "submap reset");
+        assert_eq!(
+            Dispatcher::Submap(Some("resize".into())).to_string(),
+            "submap resize"
+        );
+        assert_eq!(
+            Dispatcher::MoveCursor { x: 960, y: 540 }.to_string(),
+            "movecursor 960 540"
+        );
+        assert_eq!(
+            Dispatcher::MoveCursorToCorner(Corner::TopLeft).to_string(),
+            "movecursortocorner 3"
+        );
+        assert_eq!(
+            Dispatcher::WorkspaceOpt(WorkspaceOption::AllFloat).to_string(),
+            "workspaceopt allfloat"
+        );
+    }
+
+    #[test]
+    fn resize_params() {
+        assert_eq!(
+            ResizeParams::relative(Length::Pixels(10), Length::Pixels(-10)).to_string(),
+            "10 -10"
+        );
+        assert_eq!(
+            ResizeParams::exact(Length::Percent(50.0), Length::Percent(100.0)).to_string(),
+            "exact 50% 100%"
+        );
+        assert_eq!(
+            ResizeParams::relative(Length::Percent(-12.5), Length::Pixels(0)).to_string(),
+            "-12.5% 0"
+        );
+        assert_eq!(
+            Dispatcher::ResizeWindowPixel(
+                ResizeParams::exact(Length::Pixels(800), Length::Pixels(600)),
+                WindowSpec::Class("kitty".into())
+            )
+            .to_string(),
+            "resizewindowpixel exact 800 600,class:kitty"
+        );
+    }
 }
@@ -1,4 +1,4 @@
-use std::str::Split;
+use std::time::Duration;
 
 use anyhow::Context as _;
 use tokio::{
@@ -6,50 +6,56 @@ use tokio::{
     net::UnixStream,
 };
 
-use super::{WindowAddress, WorkspaceId, hyprland_rundir};
+use super::{
+    WindowAddress, WorkspaceId, hyprland_rundir,
+    intern::{Interned, intern},
+};
 
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub enum HyprlandEvent {
     /// Emitted on workspace change. Is emitted ONLY when a user requests a workspace change, and
     /// is not emitted on mouse movements (see activemon)
-    WorkspaceChanged { id: WorkspaceId, name: String },
+    WorkspaceChanged { id: WorkspaceId, name: Interned },
     /// Emitted on the active monitor being changed.
-    FocusedMonitor { name: String, workspace: String },
+    FocusedMonitor { name: Interned, workspace: Interned },
     /// Emitted on the active window being changed.
     ActiveWindow { address: Option<WindowAddress> },
     /// Emitted when a fullscreen status of a window changes.
     Fullscreen { enter: bool },
     /// Emitted when a monitor is removed (disconnected)
-    MonitorRemoved { name: String },
+    MonitorRemoved { name: Interned },
     /// Emitted when a monitor is added (connected)
     MonitorAdded {
         id: WorkspaceId,
-        name: String,
+        name: Interned,
         description: String,
     },
     /// Emitted when a workspace is created
-    CreateWorkspace { id: WorkspaceId, name: String },
+    CreateWorkspace { id: WorkspaceId, name: Interned },
     /// Emitted when a workspace is destroyed
-    DestroyWorkspace { id: WorkspaceId, name: String },
+    DestroyWorkspace { id: WorkspaceId, name: Interned },
     /// Emitted when a workspace is moved to a different monitor
     MoveWorkspace {
         id: WorkspaceId,
-        name: String,
-        monitor: String,
+        name: Interned,
+        monitor: Interned,
     },
     /// Emitted when a workspace is renamed
-    RenameWorkspace { id: WorkspaceId, new_name: String },
+    RenameWorkspace { id: WorkspaceId, new_name: Interned },
     /// Emitted when the special workspace opened in a monitor changes (closing results in an empty
     /// WORKSPACENAME)
-    ActiveSpecial { workspace: String, monitor: String },
+    ActiveSpecial {
+        workspace: Interned,
+        monitor: Interned,
+    },
     /// Emitted on a layout change of the active keyboard
     ActiveLayout { keyboard: String, layout: String },
     /// Emitted when a window is opened
     OpenWindow {
         address: WindowAddress,
-        workspace: String,
-        class: String,
-        title: String,
+        workspace: Interned,
+        class: Interned,
+        title: Interned,
     },
     /// Emitted when a window is closed
     CloseWindow { address: WindowAddress },
@@ -57,7 +63,7 @@ pub enum HyprlandEvent {
     MoveWindow {
         address: WindowAddress,
         workspace_id: WorkspaceId,
-        workspace: String,
+        workspace: Interned,
     },
     /// Emitted when a layerSurface is mapped
     OpenLayer { namespace: String },
@@ -77,7 +83,7 @@ pub enum HyprlandEvent {
     /// Emitted when a window title changes.
     WindowTitle {
         address: WindowAddress,
-        title: String,
+        title: Interned,
     },
     /// Emitted when togglegroup command is used.
     ToggleGroup {
@@ -99,6 +105,82 @@ pub enum HyprlandEvent {
         address: WindowAddress,
         pinned: bool,
     },
+    /// Not sent by Hyprland: emitted by [`EventStream::listen`] after connecting again, e.g. to a
+    /// restarted instance. Events may have been missed in the meantime, so any state built from
+    /// them should be fetched again.
+    Reconnected,
+}
+
+/// The kind of a [`HyprlandEvent`], without its data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EventKind {
+    WorkspaceChanged,
+    FocusedMonitor,
+    ActiveWindow,
+    Fullscreen,
+    MonitorRemoved,
+    MonitorAdded,
+    CreateWorkspace,
+    DestroyWorkspace,
+    MoveWorkspace,
+    RenameWorkspace,
+    ActiveSpecial,
+    ActiveLayout,
+    OpenWindow,
+    CloseWindow,
+    MoveWindow,
+    OpenLayer,
+    CloseLayer,
+    SubMap,
+    ChangeFloatingMode,
+    Urgent,
+    Screencast,
+    WindowTitle,
+    ToggleGroup,
+    MoveIntoGroup,
+    MoveOutOfGroup,
+    IgnoreGroupLock,
+    LockGroups,
+    ConfigReloaded,
+    Pin,
+    Reconnected,
+}
+
+impl HyprlandEvent {
+    pub fn kind(&self) -> EventKind {
+        match self {
+            HyprlandEvent::WorkspaceChanged { .. } => EventKind::WorkspaceChanged,
+            HyprlandEvent::FocusedMonitor { .. } => EventKind::FocusedMonitor,
+            HyprlandEvent::ActiveWindow { .. } => EventKind::ActiveWindow,
+            HyprlandEvent::Fullscreen { .. } => EventKind::Fullscreen,
+            HyprlandEvent::MonitorRemoved { .. } => EventKind::MonitorRemoved,
+            HyprlandEvent::MonitorAdded { .. } => EventKind::MonitorAdded,
+            HyprlandEvent::CreateWorkspace { .. } => EventKind::CreateWorkspace,
+            HyprlandEvent::DestroyWorkspace { .. } => EventKind::DestroyWorkspace,
+            HyprlandEvent::MoveWorkspace { .. } => EventKind::MoveWorkspace,
+            HyprlandEvent::RenameWorkspace { .. } => EventKind::RenameWorkspace,
+            HyprlandEvent::ActiveSpecial { .. } => EventKind::ActiveSpecial,
+            HyprlandEvent::ActiveLayout { .. } => EventKind::ActiveLayout,
+            HyprlandEvent::OpenWindow { .. } => EventKind::OpenWindow,
+            HyprlandEvent::CloseWindow { .. } => EventKind::CloseWindow,
+            HyprlandEvent::MoveWindow { .. } => EventKind::MoveWindow,
+            HyprlandEvent::OpenLayer { .. } => EventKind::OpenLayer,
+            HyprlandEvent::CloseLayer { .. } => EventKind::CloseLayer,
+            HyprlandEvent::SubMap { .. } => EventKind::SubMap,
+            HyprlandEvent::ChangeFloatingMode { .. } => EventKind::ChangeFloatingMode,
+            HyprlandEvent::Urgent { .. } => EventKind::Urgent,
+            HyprlandEvent::Screencast { .. } => EventKind::Screencast,
+            HyprlandEvent::WindowTitle { .. } => EventKind::WindowTitle,
+            HyprlandEvent::ToggleGroup { .. } => EventKind::ToggleGroup,
+            HyprlandEvent::MoveIntoGroup { .. } => EventKind::MoveIntoGroup,
+            HyprlandEvent::MoveOutOfGroup { .. } => EventKind::MoveOutOfGroup,
+            HyprlandEvent::IgnoreGroupLock { .. } => EventKind::IgnoreGroupLock,
+            HyprlandEvent::LockGroups { .. } => EventKind::LockGroups,
+            HyprlandEvent::ConfigReloaded => EventKind::ConfigReloaded,
+            HyprlandEvent::Pin { .. } => EventKind::Pin,
+            HyprlandEvent::Reconnected => EventKind::Reconnected,
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -108,31 +190,67 @@ pub enum ScreencastOwner {
 }
 pub struct EventStream;
 
+/// How long to wait before reconnecting after the event socket went away.
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
 impl EventStream {
+    async fn connect() -> anyhow::Result<BufReader<UnixStream>> {
+        let path = hyprland_rundir()?.join(".socket2.sock");
+
+        let stream = UnixStream::connect(&path)
+            .await
+            .context("failed to connect to event stream")?;
+
+        Ok(BufReader::new(stream))
+    }
+
+    /// Listens for events, reconnecting (and re-resolving the Hyprland instance) whenever the
+    /// socket is closed or cannot be connected to. Every connection after the first attempt is
+    /// announced with [`HyprlandEvent::Reconnected`].
     pub async gen fn listen() -> io::Result<HyprlandEvent> {
-        let stream = try {
-            let path = hyprland_rundir()?.join(".socket2.sock");
+        let mut first_attempt = true;
 
-            let stream = UnixStream::connect(&path)
-                .await
-                .context("failed to connect to event stream")?;
+        loop {
+            let reconnecting = !std::mem::replace(&mut first_attempt, false);
 
-            BufReader::new(stream)
-        };
+            let mut stream = match Self::connect().await {
+                Ok(stream) => stream,
+                Err(e) => {
+                    yield Err(io::Error::new::<anyhow::Error>(io::ErrorKind::Other, e));
+                    tokio::time::sleep(RECONNECT_DELAY).await;
+                    continue;
+                }
+            };
 
-        let mut stream = match stream {
-            Ok(stream) => stream,
-            Err(e) => {
-                yield Err(io::Error::new::<anyhow::Error>(io::ErrorKind::Other, e));
-                return;
+            if reconnecting {
+                yield Ok(HyprlandEvent::Reconnected);
+            }
+
+            for await event in Self::read_events(&mut stream) {
+                yield event;
             }
-        };
 
+            tokio::time::sleep(RECONNECT_DELAY).await;
+        }
+    }
+
+    async gen fn read_events(stream: &mut BufReader<UnixStream>) -> io::Result<HyprlandEvent> {
         loop {
             let mut line = String::new();
-            if let Err(e) = stream.read_line(&mut line).await {
-                yield Err(e);
-                continue;
+            match stream.read_line(&mut line).await {
+                Ok(0) => {
+                    yield Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "event stream closed",
+                    ));
+
+                    return;
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    yield Err(e);
+                    continue;
+                }
             }
 
             line.pop(); // remove newline
@@ -146,150 +264,217 @@ impl EventStream {
                 continue;
             };
 
-            let mut data = DataParser::new(data);
-
-            yield try {
-                match event {
-                    "workspacev2" => HyprlandEvent::WorkspaceChanged {
-                        id: data.next_workspace_id()?,
-                        name: data.next_string()?,
-                    },
-                    "focusedmon" => HyprlandEvent::FocusedMonitor {
-                        name: data.next_string()?,
-                        workspace: data.next_string()?,
-                    },
-                    "activewindowv2" => HyprlandEvent::ActiveWindow {
-                        address: data.next_maybe_window_address()?,
-                    },
-                    "fullscreen" => HyprlandEvent::Fullscreen {
-                        enter: data.next_bool()?,
-                    },
-                    "monitorremoved" => HyprlandEvent::MonitorRemoved {
-                        name: data.next_string()?,
-                    },
-                    "monitoraddedv2" => HyprlandEvent::MonitorAdded {
-                        id: data.next_workspace_id()?,
-                        name: data.next_string()?,
-                        description: data.next_string()?,
-                    },
-                    "createworkspacev2" => HyprlandEvent::CreateWorkspace {
-                        id: data.next_workspace_id()?,
-                        name: data.next_string()?,
-                    },
-                    "destroyworkspacev2" => HyprlandEvent::DestroyWorkspace {
-                        id: data.next_workspace_id()?,
-                        name: data.next_string()?,
-                    },
-                    "moveworkspacev2" => HyprlandEvent::MoveWorkspace {
-                        id: data.next_workspace_id()?,
-                        name: data.next_string()?,
-                        monitor: data.next_string()?,
-                    },
-                    "renameworkspace" => HyprlandEvent::RenameWorkspace {
-                        id: data.next_workspace_id()?,
-                        new_name: data.next_string()?,
-                    },
-                    "activespecial" => HyprlandEvent::ActiveSpecial {
-                        workspace: data.next_string()?,
-                        monitor: data.next_string()?,
-                    },
-                    "activelayout" => HyprlandEvent::ActiveLayout {
-                        keyboard: data.next_string()?,
-                        layout: data.next_string()?,
-                    },
-                    "openwindow" => HyprlandEvent::OpenWindow {
-                        address: data.next_window_address()?,
-                        workspace: data.next_string()?,
-                        class: data.next_string()?,
-                        title: data.next_string()?,
-                    },
-                    "closewindow" => HyprlandEvent::CloseWindow {
-                        address: data.next_window_address()?,
-                    },
-                    "movewindowv2" => HyprlandEvent::MoveWindow {
-                        address: data.next_window_address()?,
-                        workspace_id: data.next_workspace_id()?,
-                        workspace: data.next_string()?,
-                    },
-                    "openlayer" => HyprlandEvent::OpenLayer {
-                        namespace: data.next_string()?,
-                    },
-                    "closelayer" => HyprlandEvent::CloseLayer {
-                        namespace: data.next_string()?,
-                    },
-                    "submap" => HyprlandEvent::SubMap {
-                        name: data.next_string()?,
-                    },
-                    "changefloatingmode" => HyprlandEvent::ChangeFloatingMode {
-                        address: data.next_window_address()?,
-                        floating: data.next_bool()?,
-                    },
-                    "urgent" => HyprlandEvent::Urgent {
-                        address: data.next_window_address()?,
-                    },
-                    "screencast" => HyprlandEvent::Screencast {
-                        state: data.next_bool()?,
-                        owner: match data.next_bool()? {
-                            false => ScreencastOwner::Monitor,
-                            true => ScreencastOwner::Window,
-                        },
-                    },
-                    "windowtitlev2" => HyprlandEvent::WindowTitle {
-                        address: data.next_window_address()?,
-                        title: data.next_string()?,
-                    },
-                    "togglegroup" => HyprlandEvent::ToggleGroup {
-                        created: data.next_bool()?,
-                        handles: data.vec_window_ids()?,
-                    },
-                    "moveintogroup" => HyprlandEvent::MoveIntoGroup {
-                        address: data.next_window_address()?,
-                    },
-                    "moveoutofgroup" => HyprlandEvent::MoveOutOfGroup {
-                        address: data.next_window_address()?,
-                    },
-                    "ignoregrouplock" => HyprlandEvent::IgnoreGroupLock {
-                        state: data.next_bool()?,
-                    },
-                    "lockgroups" => HyprlandEvent::LockGroups {
-                        state: data.next_bool()?,
-                    },
-                    "configreloaded" => HyprlandEvent::ConfigReloaded,
-                    "pin" => HyprlandEvent::Pin {
-                        address: data.next_window_address()?,
-                        pinned: data.next_bool()?,
-                    },
-                    "workspace" | "activewindow" | "monitoradded" | "createworkspace"
-                    | "destroyworkspace" | "moveworkspace" | "movewindow" | "windowtitle" => {
-                        // ignore old events
-                        continue;
-                    }
-                    _ => do yeet io::Error::new(io::ErrorKind::InvalidData, "unknown event"),
-                }
-            };
+            match HyprlandEvent::parse(event, data) {
+                Ok(Some(event)) => yield Ok(event),
+                Ok(None) => {}
+                Err(e) => yield Err(e),
+            }
         }
     }
 }
 
-struct DataParser<'a>(Split<'a, char>);
+impl HyprlandEvent {
+    /// Parses an event line, already split at `>>`. Returns `None` for events that are ignored
+    /// in favour of their newer versions.
+    fn parse(event: &str, data: &str) -> io::Result<Option<Self>> {
+        let mut data = DataParser::new(data);
+
+        let event = match event {
+            "workspacev2" => HyprlandEvent::WorkspaceChanged {
+                id: data.next_workspace_id()?,
+                name: data.rest_interned()?,
+            },
+            "focusedmon" => HyprlandEvent::FocusedMonitor {
+                name: data.next_interned()?,
+                workspace: data.rest_interned()?,
+            },
+            "activewindowv2" => HyprlandEvent::ActiveWindow {
+                address: data.next_maybe_window_address()?,
+            },
+            "fullscreen" => HyprlandEvent::Fullscreen {
+                enter: data.next_bool()?,
+            },
+            "monitorremoved" => HyprlandEvent::MonitorRemoved {
+                name: data.rest_interned()?,
+            },
+            "monitoraddedv2" => HyprlandEvent::MonitorAdded {
+                id: data.next_workspace_id()?,
+                name: data.next_interned()?,
+                description: data.rest_string()?,
+            },
+            "createworkspacev2" => HyprlandEvent::CreateWorkspace {
+                id: data.next_workspace_id()?,
+                name: data.rest_interned()?,
+            },
+            "destroyworkspacev2" => HyprlandEvent::DestroyWorkspace {
+                id: data.next_workspace_id()?,
+                name: data.rest_interned()?,
+            },
+            "moveworkspacev2" => {
+                let id = data.next_workspace_id()?;
+                let monitor = data.last_interned()?;
+
+                HyprlandEvent::MoveWorkspace {
+                    id,
+                    name: data.rest_interned()?,
+                    monitor,
+                }
+            }
+            "renameworkspace" => HyprlandEvent::RenameWorkspace {
+                id: data.next_workspace_id()?,
+                new_name: data.rest_interned()?,
+            },
+            "activespecial" => {
+                let monitor = data.last_interned()?;
+
+                HyprlandEvent::ActiveSpecial {
+                    workspace: data.rest_interned()?,
+                    monitor,
+                }
+            }
+            "activelayout" => HyprlandEvent::ActiveLayout {
+                keyboard: data.next_string()?,
+                layout: data.rest_string()?,
+            },
+            "openwindow" => HyprlandEvent::OpenWindow {
+                address: data.next_window_address()?,
+                workspace: data.next_interned()?,
+                class: data.next_interned()?,
+                title: data.rest_interned()?,
+            },
+            "closewindow" => HyprlandEvent::CloseWindow {
+                address: data.next_window_address()?,
+            },
+            "movewindowv2" => HyprlandEvent::MoveWindow {
+                address: data.next_window_address()?,
+                workspace_id: data.next_workspace_id()?,
+                workspace: data.rest_interned()?,
+            },
+            "openlayer" => HyprlandEvent::OpenLayer {
+                namespace: data.rest_string()?,
+            },
+            "closelayer" => HyprlandEvent::CloseLayer {
+                namespace: data.rest_string()?,
+            },
+            "submap" => HyprlandEvent::SubMap {
+                name: data.rest_string()?,
+            },
+            "changefloatingmode" => HyprlandEvent::ChangeFloatingMode {
+                address: data.next_window_address()?,
+                floating: data.next_bool()?,
+            },
+            "urgent" => HyprlandEvent::Urgent {
+                address: data.next_window_address()?,
+            },
+            "screencast" => HyprlandEvent::Screencast {
+                state: data.next_bool()?,
+                owner: match data.next_bool()? {
+                    false => ScreencastOwner::Monitor,
+                    true => ScreencastOwner::Window,
+                },
+            },
+            "windowtitlev2" => HyprlandEvent::WindowTitle {
+                address: data.next_window_address()?,
+                title: data.rest_interned()?,
+            },
+            "togglegroup" => HyprlandEvent::ToggleGroup {
+                created: data.next_bool()?,
+                handles: data.vec_window_ids()?,
+            },
+            "moveintogroup" => HyprlandEvent::MoveIntoGroup {
+                address: data.next_window_address()?,
+            },
+            "moveoutofgroup" => HyprlandEvent::MoveOutOfGroup {
+                address: data.next_window_address()?,
+            },
+            "ignoregrouplock" => HyprlandEvent::IgnoreGroupLock {
+                state: data.next_bool()?,
+            },
+            "lockgroups" => HyprlandEvent::LockGroups {
+                state: data.next_bool()?,
+            },
+            "configreloaded" => HyprlandEvent::ConfigReloaded,
+            "pin" => HyprlandEvent::Pin {
+                address: data.next_window_address()?,
+                pinned: data.next_bool()?,
+            },
+            "workspace" | "activewindow" | "monitoradded" | "createworkspace"
+            | "destroyworkspace" | "moveworkspace" | "movewindow" | "windowtitle" => {
+                // ignore old events
+                return Ok(None);
+            }
+            _ => do yeet io::Error::new(io::ErrorKind::InvalidData, "unknown event"),
+        };
+
+        Ok(Some(event))
+    }
+}
+
+/// Splits an event's data at commas. Names and titles can contain commas themselves, so the last
+/// field is read with one of the `rest_*` methods, which take the remainder of the line. When a
+/// name is followed by fields that can't contain commas (like monitor names), those are read from
+/// the end with the `last_*` methods first.
+struct DataParser<'a>(Option<&'a str>);
 
 impl<'a> DataParser<'a> {
     fn new(data: &'a str) -> Self {
-        Self(data.split(','))
+        Self(Some(data))
     }
 
-    fn next(&mut self) -> io::Result<&str> {
+    fn next(&mut self) -> io::Result<&'a str> {
+        let data = self.rest()?;
+
+        match data.split_once(',') {
+            Some((field, rest)) => {
+                self.0 = Some(rest);
+                Ok(field)
+            }
+            None => Ok(data),
+        }
+    }
+
+    fn rest(&mut self) -> io::Result<&'a str> {
         self.0
-            .next()
+            .take()
             .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "unexpected end of data"))
     }
 
+    /// Takes the field at the end of the data.
+    fn last(&mut self) -> io::Result<&'a str> {
+        let data = self.rest()?;
+
+        match data.rsplit_once(',') {
+            Some((rest, field)) => {
+                self.0 = Some(rest);
+                Ok(field)
+            }
+            None => Ok(data),
+        }
+    }
+
+    fn last_interned(&mut self) -> io::Result<Interned> {
+        self.last().map(intern)
+    }
+
+    fn rest_string(&mut self) -> io::Result<String> {
+        self.rest().map(ToOwned::to_owned)
+    }
+
+    fn rest_interned(&mut self) -> io::Result<Interned> {
+        self.rest().map(intern)
+    }
+
     fn next_string(&mut self) -> io::Result<String> {
         self.next().map(ToOwned::to_owned)
     }
 
+    fn next_interned(&mut self) -> io::Result<Interned> {
+        self.next().map(intern)
+    }
+
     fn next_workspace_id(&mut self) -> io::Result<WorkspaceId> {
-        i32::from_str_radix(self.next()?, 16)
+        self.next()?
+            .parse()
             .map(WorkspaceId)
             .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "invalid integer"))
     }
@@ -312,16 +497,125 @@ impl<'a> DataParser<'a> {
             .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "invalid integer"))
     }
 
+    /// Hyprland sends booleans as `0` or `1`.
     fn next_bool(&mut self) -> io::Result<bool> {
-        self.next()?
-            .parse()
-            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "invalid boolean"))
+        match self.next()? {
+            "0" => Ok(false),
+            "1" => Ok(true),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "invalid boolean",
+            )),
+        }
     }
 
     fn vec_window_ids(self) -> io::Result<Vec<WindowAddress>> {
         self.0
-            .map(|s| s.parse().map(WindowAddress))
+            .into_iter()
+            .flat_map(|data| data.split(','))
+            .map(str::parse)
             .collect::<Result<Vec<_>, _>>()
             .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "invalid window address"))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn title_with_commas() {
+        let event =
+            HyprlandEvent::parse("windowtitlev2", "55d0c8a0e2f0,Inbox (3), Gmail — Firefox");
+        assert_eq!(
+            event.unwrap(),
+            Some(HyprlandEvent::WindowTitle {
+                address: WindowAddress(0x55d0c8a0e2f0),
+                title: intern("Inbox (3), Gmail — Firefox"),
+            })
+        );
+    }
+
+    #[test]
+    fn numeric_bools() {
+        let event = HyprlandEvent::parse("pin", "55d0c8a0e2f0,1");
+        assert_eq!(
+            event.unwrap(),
+            Some(HyprlandEvent::Pin {
+                address: WindowAddress(0x55d0c8a0e2f0),
+                pinned: true,
+            })
+        );
+
+        let event = HyprlandEvent::parse("fullscreen", "0");
+        assert_eq!(
+            event.unwrap(),
+            Some(HyprlandEvent::Fullscreen { enter: false })
+        );
+
+        assert!(HyprlandEvent::parse("fullscreen", "true").is_err());
+    }
+
+    #[test]
+    fn workspace_name_with_commas() {
+        let event = HyprlandEvent::parse("moveworkspacev2", "4,a,b,DP-1");
+        assert_eq!(
+            event.unwrap(),
+            Some(HyprlandEvent::MoveWorkspace {
+                id: WorkspaceId(4),
+                name: intern("a,b"),
+                monitor: intern("DP-1"),
+            })
+        );
+
+        let event = HyprlandEvent::parse("activespecial", "special:a,b,DP-1");
+        assert_eq!(
+            event.unwrap(),
+            Some(HyprlandEvent::ActiveSpecial {
+                workspace: intern("special:a,b"),
+                monitor: intern("DP-1"),
+            })
+        );
+
+        // closing the special workspace leaves the name empty
+        let event = HyprlandEvent::parse("activespecial", ",DP-1");
+        assert_eq!(
+            event.unwrap(),
+            Some(HyprlandEvent::ActiveSpecial {
+                workspace: intern(""),
+                monitor: intern("DP-1"),
+            })
+        );
+    }
+
+    #[test]
+    fn decimal_workspace_ids() {
+        let event = HyprlandEvent::parse("movewindowv2", "55d0c8a0e2f0,10,10");
+        assert_eq!(
+            event.unwrap(),
+            Some(HyprlandEvent::MoveWindow {
+                address: WindowAddress(0x55d0c8a0e2f0),
+                workspace_id: WorkspaceId(10),
+                workspace: intern("10"),
+            })
+        );
+
+        let event = HyprlandEvent::parse("movewindowv2", "55d0c8a0e2f0,-98,special:scratch");
+        assert_eq!(
+            event.unwrap(),
+            Some(HyprlandEvent::MoveWindow {
+                address: WindowAddress(0x55d0c8a0e2f0),
+                workspace_id: WorkspaceId(-98),
+                workspace: intern("special:scratch"),
+            })
+        );
+    }
+
+    #[test]
+    fn old_events_are_ignored() {
+        assert_eq!(
+            HyprlandEvent::parse("windowtitle", "55d0c8a0e2f0").unwrap(),
+            None
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use super::events::{EventKind, EventStream, HyprlandEvent};
+
+type HookFuture = Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send>>;
+type Hook = Arc<dyn Fn(HyprlandEvent) -> HookFuture + Send + Sync>;
+
+/// Runs async callbacks for Hyprland events, for daemons that want to react to the event stream
+/// without writing their own dispatch loop.
+///
+/// Each hook invocation runs in its own tokio task, so a hook that fails, panics or takes a long
+/// time does not affect other hooks or the delivery of later events.
+#[derive(Default)]
+pub struct HookRegistry {
+    hooks: HashMap<EventKind, Vec<Hook>>,
+}
+
+impl HookRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a hook to run for every event of the given kind.
+    pub fn on<F, Fut>(&mut self, kind: EventKind, hook: F) -> &mut Self
+    where
+        F: Fn(HyprlandEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+    {
+        self.hooks
+            .entry(kind)
+            .or_default()
+            .push(Arc::new(move |event| Box::pin(hook(event))));
+
+        self
+    }
+
+    /// Spawns the hooks registered for the event's kind. Must be called within a tokio runtime.
+    pub fn dispatch(&self, event: &HyprlandEvent) {
+        let Some(hooks) = self.hooks.get(&event.kind()) else {
+            return;
+        };
+
+        for hook in hooks {
+            let future = hook(event.clone());
+            let kind = event.kind();
+
+            tokio::spawn(async move {
+                if let Err(e) = future.await {
+                    eprintln!("Error in {kind:?} hook: {e:?}");
+                }
+            });
+        }
+    }
+
+    /// Listens for events forever, dispatching each to the registered hooks.
+    pub async fn run(self) {
+        for await event in EventStream::listen() {
+            match event {
+                Ok(event) => self.dispatch(&event),
+                Err(event) => eprintln!("Error: {:?}", event),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    #[test]
+    fn hooks_run_for_their_kind_only() {
+        let reloaded = Arc::new(AtomicUsize::new(0));
+        let urgent = Arc::new(AtomicUsize::new(0));
+
+        let mut registry = HookRegistry::new();
+        registry
+            .on(EventKind::ConfigReloaded, |_| async {
+                panic!("hook panicked")
+            })
+            .on(EventKind::ConfigReloaded, |_| async {
+                Err(anyhow::anyhow!("hook failed"))
+            })
+            .on(EventKind::ConfigReloaded, {
+                let reloaded = reloaded.clone();
+                move |_| {
+                    let reloaded = reloaded.clone();
+                    async move {
+                        reloaded.fetch_add(1, Ordering::SeqCst);
+                        Ok(())
+                    }
+                }
+            })
+            .on(EventKind::Urgent, {
+                let urgent = urgent.clone();
+                move |_| {
+                    let urgent = urgent.clone();
+                    async move {
+                        urgent.fetch_add(1, Ordering::SeqCst);
+                        Ok(())
+                    }
+                }
+            });
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+
+        runtime.block_on(async {
+            registry.dispatch(&HyprlandEvent::ConfigReloaded);
+
+            // let the spawned hooks run
+            for _ in 0..10 {
+                tokio::task::yield_now().await;
+            }
+        });
+
+        assert_eq!(reloaded.load(Ordering::SeqCst), 1);
+        assert_eq!(urgent.load(Ordering::SeqCst), 0);
+    }
+}
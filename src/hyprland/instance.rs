@@ -0,0 +1,187 @@
+use std::cmp::Reverse;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use anyhow::Context as _;
+
+/// Signature explicitly chosen with [`Instances::select`], taking precedence over the environment.
+static SELECTED: Mutex<Option<OsString>> = Mutex::new(None);
+
+/// A running Hyprland instance, found in `$XDG_RUNTIME_DIR/hypr`.
+#[derive(Clone, Debug)]
+pub struct Instance {
+    pub signature: OsString,
+    /// The runtime directory holding the instance's sockets.
+    pub dir: PathBuf,
+    pub started: SystemTime,
+}
+
+pub struct Instances;
+
+impl Instances {
+    /// Makes all further connections go to the instance with the given signature.
+    pub fn select(signature: impl Into<OsString>) {
+        *SELECTED.lock().unwrap() = Some(signature.into());
+    }
+
+    /// Lists all live instances, most recently started first.
+    pub fn list() -> anyhow::Result<Vec<Instance>> {
+        let hypr_dir = runtime_dir().join("hypr");
+
+        let mut instances = Vec::new();
+        for entry in std::fs::read_dir(&hypr_dir)
+            .with_context(|| format!("failed to read {}", hypr_dir.display()))?
+        {
+            let entry = entry?;
+            if let Some(instance) = Instance::load(entry.file_name(), entry.path()) {
+                instances.push(instance);
+            }
+        }
+
+        instances.sort_by_key(|instance| Reverse(instance.started));
+
+        Ok(instances)
+    }
+
+    /// Returns the live instance with the given signature.
+    pub fn get(signature: impl Into<OsString>) -> anyhow::Result<Instance> {
+        let signature = signature.into();
+        let dir = runtime_dir().join("hypr").join(&signature);
+
+        Instance::load(signature.clone(), dir).with_context(|| {
+            format!(
+                "Hyprland instance {} is not running",
+                signature.to_string_lossy()
+            )
+        })
+    }
+
+    pub fn newest() -> anyhow::Result<Instance> {
+        Self::list()?
+            .into_iter()
+            .next()
+            .context("no running Hyprland instance found")
+    }
+
+    /// Resolves the instance to talk to: the one chosen with [`Instances::select`] if any,
+    /// otherwise the one named by `HYPRLAND_INSTANCE_SIGNATURE`, falling back to the most recently
+    /// started one when that is unset or gone (e.g. after Hyprland was restarted underneath us).
+    pub fn current() -> anyhow::Result<Instance> {
+        if let Some(signature) = SELECTED.lock().unwrap().clone() {
+            return Self::get(signature);
+        }
+
+        let from_env = std::env::var_os("HYPRLAND_INSTANCE_SIGNATURE")
+            .and_then(|signature| Self::get(signature).ok());
+
+        match from_env {
+            Some(instance) => Ok(instance),
+            None => Self::newest(),
+        }
+    }
+}
+
+impl Instance {
+    /// Returns `None` if the directory does not belong to a live instance.
+    fn load(signature: OsString, dir: PathBuf) -> Option<Self> {
+        let lock = dir.join("hyprland.lock");
+
+        // the lock file starts with the compositor's pid; directories of crashed instances stick
+        // around, so check that it's still running
+        if let Ok(contents) = std::fs::read_to_string(&lock) {
+            let pid: u32 = contents.lines().next()?.trim().parse().ok()?;
+            if !Path::new("/proc").join(pid.to_string()).exists() {
+                return None;
+            }
+        }
+
+        let started = dir
+            .join(".socket.sock")
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()?;
+
+        Some(Self {
+            signature,
+            dir,
+            started,
+        })
+    }
+}
+
+fn runtime_dir() -> PathBuf {
+    match std::env::var_os("XDG_RUNTIME_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from("/run/user").join(nix::unistd::Uid::current().to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::time::Duration;
+
+    use super::*;
+
+    /// Creates an instance directory with the given lock file contents, started `age` ago.
+    fn fake_instance(hypr_dir: &Path, signature: &str, lock: &str, age: Duration) {
+        let dir = hypr_dir.join(signature);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("hyprland.lock"), lock).unwrap();
+
+        let socket = File::create(dir.join(".socket.sock")).unwrap();
+        socket.set_modified(SystemTime::now() - age).unwrap();
+    }
+
+    fn signatures(instances: Vec<Instance>) -> Vec<OsString> {
+        instances.into_iter().map(|i| i.signature).collect()
+    }
+
+    // everything touching the environment is in one test, so tests running in parallel can't see
+    // each other's changes
+    #[test]
+    fn instances() {
+        let runtime_dir = std::env::temp_dir().join(format!("rdls-test-{}", std::process::id()));
+        let hypr_dir = runtime_dir.join("hypr");
+        let live = std::process::id().to_string();
+
+        fake_instance(&hypr_dir, "old", &live, Duration::from_secs(60));
+        fake_instance(&hypr_dir, "new", &live, Duration::from_secs(10));
+        // pids are always below the maximum pid_max
+        fake_instance(&hypr_dir, "crashed", "4194304", Duration::ZERO);
+        fake_instance(&hypr_dir, "empty", "\n", Duration::ZERO);
+        fake_instance(&hypr_dir, "garbage", "hyprland\n", Duration::ZERO);
+
+        // SAFETY: no other test reads or writes these variables
+        unsafe {
+            std::env::set_var("XDG_RUNTIME_DIR", &runtime_dir);
+            std::env::remove_var("HYPRLAND_INSTANCE_SIGNATURE");
+        }
+
+        assert_eq!(signatures(Instances::list().unwrap()), ["new", "old"]);
+        assert!(Instances::get("crashed").is_err());
+        assert!(Instances::get("empty").is_err());
+        assert!(Instances::get("garbage").is_err());
+
+        // newest live instance when nothing else says otherwise
+        assert_eq!(Instances::current().unwrap().signature, "new");
+
+        // the environment, unless that instance is gone
+        unsafe { std::env::set_var("HYPRLAND_INSTANCE_SIGNATURE", "old") };
+        assert_eq!(Instances::current().unwrap().signature, "old");
+        unsafe { std::env::set_var("HYPRLAND_INSTANCE_SIGNATURE", "crashed") };
+        assert_eq!(Instances::current().unwrap().signature, "new");
+
+        // an explicit selection wins, and doesn't fall back
+        unsafe { std::env::set_var("HYPRLAND_INSTANCE_SIGNATURE", "new") };
+        Instances::select("old");
+        assert_eq!(Instances::current().unwrap().signature, "old");
+        Instances::select("crashed");
+        assert!(Instances::current().is_err());
+
+        *SELECTED.lock().unwrap() = None;
+        std::fs::remove_dir_all(&runtime_dir).unwrap();
+    }
+}
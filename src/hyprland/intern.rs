@@ -0,0 +1,115 @@
+use std::collections::HashSet;
+use std::fmt::{self, Debug, Display};
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::sync::{Arc, Mutex};
+
+use once_cell::sync::Lazy;
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+
+static STRINGS: Lazy<Mutex<HashSet<Arc<str>>>> = Lazy::new(Default::default);
+
+/// A shared, deduplicated string. Workspace names, window classes and titles repeat heavily across
+/// clients and events, so they are stored once and compared by pointer where possible.
+#[derive(Clone, Eq)]
+pub struct Interned(Arc<str>);
+
+pub fn intern(s: &str) -> Interned {
+    let mut strings = STRINGS.lock().unwrap();
+
+    if let Some(existing) = strings.get(s) {
+        return Interned(existing.clone());
+    }
+
+    // before growing the table, drop strings nobody refers to anymore (e.g. old window titles)
+    if strings.len() == strings.capacity() {
+        strings.retain(|s| Arc::strong_count(s) > 1);
+    }
+
+    let new = Arc::<str>::from(s);
+    strings.insert(new.clone());
+
+    Interned(new)
+}
+
+impl Interned {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Deref for Interned {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq for Interned {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0) || self.0 == other.0
+    }
+}
+
+impl Hash for Interned {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+impl Debug for Interned {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&*self.0, f)
+    }
+}
+
+impl Display for Interned {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&*self.0, f)
+    }
+}
+
+impl<'de> Deserialize<'de> for Interned {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct InternedVisitor;
+
+        impl Visitor<'_> for InternedVisitor {
+            type Value = Interned;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a string")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Interned, E> {
+                Ok(intern(v))
+            }
+        }
+
+        deserializer.deserialize_str(InternedVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interned_strings_are_shared() {
+        let a = intern("firefox");
+        let b = intern("firefox");
+        assert!(Arc::ptr_eq(&a.0, &b.0));
+        assert_eq!(a, b);
+        assert_ne!(a, intern("kitty"));
+    }
+
+    #[test]
+    fn deserialize() {
+        let names: Vec<Interned> = serde_json::from_str(r#"["1", "1", "web"]"#).unwrap();
+        assert!(Arc::ptr_eq(&names[0].0, &names[1].0));
+        assert_eq!(names[2].as_str(), "web");
+    }
+}
@@ -1,47 +1,167 @@
+use std::fmt::Display;
+use std::num::ParseIntError;
+use std::ops::BitOr;
 use std::path::PathBuf;
+use std::str::FromStr;
 
-use anyhow::Context as _;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+
+use self::instance::Instances;
 
 pub mod commands;
 pub mod dispatch;
 pub mod events;
+pub mod hooks;
+pub mod instance;
+pub mod intern;
 
 fn hyprland_rundir() -> anyhow::Result<PathBuf> {
-    let uid = nix::unistd::Uid::current();
-    let signature = std::env::var_os("HYPRLAND_INSTANCE_SIGNATURE")
-        .context("HYPRLAND_INSTANCE_SIGNATURE not set")?;
-
-    Ok(PathBuf::from("/run/user")
-        .join(uid.to_string())
-        .join("hypr")
-        .join(signature))
+    Instances::current().map(|instance| instance.dir)
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
 #[serde(transparent)]
 pub struct WorkspaceId(pub i32);
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+impl Display for WorkspaceId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for WorkspaceId {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse().map(Self)
+    }
+}
+
+/// A window's address, written as `0x`-prefixed hex like hyprland does.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
 #[serde(transparent)]
 pub struct WindowAddress(#[serde(with = "window_address_serde")] pub u64);
 
+impl Display for WindowAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "0x{:x}", self.0)
+    }
+}
+
+/// Accepts the address with or without the `0x` prefix, as events leave it out.
+impl FromStr for WindowAddress {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        u64::from_str_radix(s.trim_start_matches("0x"), 16).map(Self)
+    }
+}
+
+/// A set of modifier keys, using the same bits as hyprland's `modmask`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Deserialize)]
+#[serde(transparent)]
+pub struct ModMask(pub u32);
+
+impl ModMask {
+    pub const SHIFT: Self = Self(1 << 0);
+    pub const CAPS: Self = Self(1 << 1);
+    pub const CTRL: Self = Self(1 << 2);
+    pub const ALT: Self = Self(1 << 3);
+    pub const MOD2: Self = Self(1 << 4);
+    pub const MOD3: Self = Self(1 << 5);
+    pub const SUPER: Self = Self(1 << 6);
+    pub const MOD5: Self = Self(1 << 7);
+
+    const NAMES: [(Self, &str); 8] = [
+        (Self::SHIFT, "SHIFT"),
+        (Self::CAPS, "CAPS"),
+        (Self::CTRL, "CTRL"),
+        (Self::ALT, "ALT"),
+        (Self::MOD2, "MOD2"),
+        (Self::MOD3, "MOD3"),
+        (Self::SUPER, "SUPER"),
+        (Self::MOD5, "MOD5"),
+    ];
+
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for ModMask {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl Display for ModMask {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut names = Self::NAMES
+            .iter()
+            .filter(|(mask, _)| self.contains(*mask))
+            .map(|(_, name)| name);
+
+        if let Some(first) = names.next() {
+            f.write_str(first)?;
+        }
+
+        for name in names {
+            write!(f, " {name}")?;
+        }
+
+        Ok(())
+    }
+}
+
 mod window_address_serde {
-    use serde::{self, Deserialize, Deserializer};
+    use serde::{self, Deserialize, Deserializer, Serializer};
 
-    // pub fn serialize<S>(value: &u64, serializer: S) -> Result<S::Ok, S::Error>
-    // where
-    //     S: Serializer,
-    // {
-    //     serializer.serialize_str(&format!("0x{:x}", value))
-    // }
+    use super::WindowAddress;
+
+    pub fn serialize<S>(value: &u64, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(&WindowAddress(*value))
+    }
 
     pub fn deserialize<'de, D>(deserializer: D) -> Result<u64, D::Error>
     where
         D: Deserializer<'de>,
     {
         let s = String::deserialize(deserializer)?;
-        let trimmed = s.trim_start_matches("0x");
-        u64::from_str_radix(trimmed, 16).map_err(serde::de::Error::custom)
+        s.parse::<WindowAddress>()
+            .map(|address| address.0)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn window_address() {
+        let address = WindowAddress(0x55d0c8a0e2f0);
+        assert_eq!(address.to_string(), "0x55d0c8a0e2f0");
+        assert_eq!("0x55d0c8a0e2f0".parse(), Ok(address));
+        assert_eq!("55d0c8a0e2f0".parse(), Ok(address));
+        assert!("0xnope".parse::<WindowAddress>().is_err());
+
+        let json = serde_json::to_string(&address).unwrap();
+        assert_eq!(json, r#""0x55d0c8a0e2f0""#);
+        assert_eq!(
+            serde_json::from_str::<WindowAddress>(&json).unwrap(),
+            address
+        );
+    }
+
+    #[test]
+    fn workspace_id() {
+        assert_eq!(WorkspaceId(-98).to_string(), "-98");
+        assert_eq!("3".parse(), Ok(WorkspaceId(3)));
+        assert_eq!(serde_json::to_string(&WorkspaceId(3)).unwrap(), "3");
     }
 }
@@ -3,24 +3,62 @@
 #![feature(try_blocks)]
 #![feature(yeet_expr)]
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
+use std::future::Future;
+use std::io;
+use std::time::Duration;
 
 use iced::futures::SinkExt as _;
-use iced::widget::{button, row};
-use iced::{Element, Subscription, Task, Theme, stream};
+use iced::widget::{button, container, row, text};
+use iced::{Element, Subscription, Task, Theme, stream, task};
 use iced_layershell::Application;
 use iced_layershell::actions::LayershellCustomActions;
 use iced_layershell::reexport::{Anchor, KeyboardInteractivity};
 use iced_layershell::settings::{LayerShellSettings, Settings};
 
-use self::hyprland::WorkspaceId;
-use self::hyprland::commands::{Client, Workspace};
+use self::hyprland::commands::{Client, Command, DEFAULT_TIMEOUT, Workspace, WorkspaceRef};
 use self::hyprland::dispatch::{Dispatcher, WorkspaceSpec};
 use self::hyprland::events::HyprlandEvent;
+use self::hyprland::instance::Instances;
+use self::hyprland::intern::intern;
+use self::hyprland::{WindowAddress, WorkspaceId};
 
 pub mod hyprland;
+pub mod systemd;
 
 fn main() -> Result<(), iced_layershell::Error> {
+    if std::env::args().any(|arg| arg == "--install-service") {
+        match systemd::install_service() {
+            Ok(path) => println!("Installed {}", path.display()),
+            Err(e) => {
+                eprintln!("Error: {:?}", e);
+                std::process::exit(1);
+            }
+        }
+
+        return Ok(());
+    }
+
+    if let Some(signature) = std::env::args()
+        .skip_while(|arg| arg != "--instance")
+        .nth(1)
+    {
+        Instances::select(signature);
+    }
+
+    let flags = Flags {
+        preview_themes: std::env::args().any(|arg| arg == "--preview-themes"),
+    };
+
+    if std::env::args().any(|arg| arg == "--windowed") {
+        if let Err(e) = run_windowed(flags) {
+            eprintln!("Error: {:?}", e);
+            std::process::exit(1);
+        }
+
+        return Ok(());
+    }
+
     Bar::run(Settings {
         layer_settings: LayerShellSettings {
             size: Some((0, 30)),
@@ -29,17 +67,51 @@ fn main() -> Result<(), iced_layershell::Error> {
             exclusive_zone: 30,
             ..Default::default()
         },
+        flags,
         ..Default::default()
     })?;
     std::thread::sleep(std::time::Duration::from_millis(1));
     Ok(())
 }
 
+/// Runs the bar as a regular window, for compositors without layer-shell support (or nested
+/// sessions during development).
+fn run_windowed(flags: Flags) -> iced::Result {
+    iced::application("rdls", Bar::update, Bar::view)
+        .subscription(Bar::subscription)
+        .theme(Bar::theme)
+        .window_size((1280., 30.))
+        .run_with(move || Bar::new(flags))
+}
+
+/// How long each theme is shown for with `--preview-themes`.
+const THEME_PREVIEW_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Window title shown with `--preview-themes`.
+const PREVIEW_TITLE: &str = "~/src/rdls — nvim";
+
+#[derive(Default)]
+struct Flags {
+    /// Cycle through all built-in themes on sample data, showing the theme name next to the
+    /// window title. Doesn't talk to hyprland at all.
+    preview_themes: bool,
+}
+
 struct Bar {
     workspaces: BTreeMap<WorkspaceId, Workspace>,
     active_workspace: Option<WorkspaceId>,
-    active_window_title: Option<String>,
+    active_window: Option<WindowAddress>,
+    /// Workspace, title, floating, pinned, fullscreen and group state are kept current from
+    /// events. Position, size and focus history are only as fresh as the last fetch.
     clients: Vec<Client>,
+    /// Clients being fetched one by one. A window that is closed before its reply arrives is
+    /// removed from here, so the reply is dropped instead of adding the closed window back.
+    pending_clients: HashSet<WindowAddress>,
+    config_errors: Vec<String>,
+    workspaces_fetch: Option<task::Handle>,
+    clients_fetch: Option<task::Handle>,
+    theme: Theme,
+    preview_themes: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -48,6 +120,12 @@ enum Message {
     HyprlandEvent(HyprlandEvent),
     UpdateWorkspaces(Vec<Workspace>),
     UpdateClients(Vec<Client>),
+    UpdateClient(WindowAddress, Option<Client>),
+    UpdateActiveWindow(Option<WindowAddress>),
+    UpdateConfigErrors(Vec<String>),
+    CommandFailed(&'static str, String),
+    Watchdog,
+    NextTheme,
 }
 
 impl TryFrom<Message> for LayershellCustomActions {
@@ -58,48 +136,176 @@ impl TryFrom<Message> for LayershellCustomActions {
     }
 }
 
-fn fetch_workspaces() -> iced::Task<Message> {
+/// Runs a hyprland query and turns its result into a message. A query that can't connect, times
+/// out or fails otherwise is reported with [`Message::CommandFailed`] instead of leaving the bar
+/// waiting or taking it down.
+fn query<T, F>(
+    name: &'static str,
+    query: impl FnOnce(Command) -> F + Send + 'static,
+    done: impl FnOnce(T) -> Message + Send + 'static,
+) -> Task<Message>
+where
+    F: Future<Output = io::Result<T>> + Send + 'static,
+{
     Task::future(async move {
-        let out = hyprland::commands::Command::new()
-            .await
-            .unwrap()
-            .workspaces()
-            .await
-            .unwrap();
-
-        Message::UpdateWorkspaces(out)
+        let result = match tokio::time::timeout(DEFAULT_TIMEOUT, Command::new()).await {
+            Ok(Ok(command)) => query(command).await.map_err(anyhow::Error::from),
+            Ok(Err(e)) => Err(e),
+            Err(_) => Err(anyhow::anyhow!(
+                "hyprland did not accept the connection in time"
+            )),
+        };
+
+        match result {
+            Ok(out) => done(out),
+            Err(e) => Message::CommandFailed(name, format!("{e:#}")),
+        }
     })
 }
 
-fn fetch_clients() -> iced::Task<Message> {
-    Task::future(async move {
-        let out = hyprland::commands::Command::new()
-            .await
-            .unwrap()
-            .clients()
-            .await
-            .unwrap();
-
-        Message::UpdateClients(out)
+fn fetch_workspaces() -> iced::Task<Message> {
+    query("workspaces", Command::workspaces, Message::UpdateWorkspaces)
+}
+
+/// Only used when there is no event to go by yet, on startup and after reconnecting, together
+/// with a full client fetch. Otherwise the active window follows [`HyprlandEvent::ActiveWindow`],
+/// which a reply from the request socket could race with.
+fn fetch_active_window() -> iced::Task<Message> {
+    query("activewindow", Command::activewindow, |client| {
+        Message::UpdateActiveWindow(client.map(|client| client.address))
     })
 }
 
+fn fetch_client(address: WindowAddress) -> iced::Task<Message> {
+    query(
+        "client",
+        move |command| command.client(address),
+        move |client| Message::UpdateClient(address, client),
+    )
+}
+
+fn fetch_config_errors() -> iced::Task<Message> {
+    query(
+        "configerrors",
+        Command::configerrors,
+        Message::UpdateConfigErrors,
+    )
+}
+
+fn fetch_clients() -> iced::Task<Message> {
+    query("clients", Command::clients, Message::UpdateClients)
+}
+
+/// Workspaces shown with `--preview-themes`.
+fn sample_workspaces() -> BTreeMap<WorkspaceId, Workspace> {
+    ["1", "2", "3", "web", "mail"]
+        .into_iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let id = WorkspaceId(i as i32 + 1);
+            let workspace = Workspace {
+                id,
+                name: intern(name),
+                monitor: intern("DP-1"),
+                monitor_id: 0,
+                windows: 1,
+                has_fullscreen: false,
+                last_window: WindowAddress(0),
+                last_window_title: intern(PREVIEW_TITLE),
+            };
+
+            (id, workspace)
+        })
+        .collect()
+}
+
+/// A red indicator shown while hyprland reports errors in its config.
+fn config_error_badge(errors: &[String]) -> Element<Message> {
+    let label = match errors.len() {
+        1 => String::from("1 config error"),
+        n => format!("{n} config errors"),
+    };
+
+    container(text(label).style(text::danger)).padding(5).into()
+}
+
+impl Bar {
+    fn client(&self, address: WindowAddress) -> Option<&Client> {
+        self.clients.iter().find(|client| client.address == address)
+    }
+
+    fn client_mut(&mut self, address: WindowAddress) -> Option<&mut Client> {
+        self.clients
+            .iter_mut()
+            .find(|client| client.address == address)
+    }
+
+    /// Refetches all workspaces, cancelling a previous fetch that is still in flight so its
+    /// outdated reply can't overwrite this one.
+    fn fetch_workspaces(&mut self) -> Task<Message> {
+        let (task, handle) = fetch_workspaces().abortable();
+        self.workspaces_fetch = Some(handle.abort_on_drop());
+        task
+    }
+
+    /// Refetches all clients, see [`Bar::fetch_workspaces`].
+    fn fetch_clients(&mut self) -> Task<Message> {
+        let (task, handle) = fetch_clients().abortable();
+        self.clients_fetch = Some(handle.abort_on_drop());
+        task
+    }
+
+    fn fetch_client(&mut self, address: WindowAddress) -> Task<Message> {
+        self.pending_clients.insert(address);
+        fetch_client(address)
+    }
+
+    fn upsert_client(&mut self, client: Client) {
+        match self.client_mut(client.address) {
+            Some(existing) => *existing = client,
+            None => self.clients.push(client),
+        }
+    }
+}
+
 impl Application for Bar {
     type Executor = iced::executor::Default;
     type Message = Message;
     type Theme = Theme;
-    type Flags = ();
-
-    fn new(_flags: ()) -> (Self, Task<Message>) {
-        (
-            Self {
-                workspaces: Default::default(),
-                active_workspace: None,
-                active_window_title: None,
-                clients: Default::default(),
-            },
-            Task::batch([fetch_workspaces(), fetch_clients()]),
-        )
+    type Flags = Flags;
+
+    fn new(flags: Flags) -> (Self, Task<Message>) {
+        if let Err(e) = systemd::notify("READY=1") {
+            eprintln!("Error: {:?}", e);
+        }
+
+        let mut bar = Self {
+            workspaces: Default::default(),
+            active_workspace: None,
+            active_window: None,
+            clients: Default::default(),
+            pending_clients: Default::default(),
+            config_errors: Default::default(),
+            workspaces_fetch: None,
+            clients_fetch: None,
+            theme: Theme::TokyoNight,
+            preview_themes: flags.preview_themes,
+        };
+
+        if bar.preview_themes {
+            bar.workspaces = sample_workspaces();
+            bar.active_workspace = Some(WorkspaceId(2));
+            return (bar, Task::none());
+        }
+
+        let task = Task::batch([
+            bar.fetch_workspaces(),
+            bar.fetch_clients(),
+            fetch_active_window(),
+            fetch_config_errors(),
+        ]);
+
+        (bar, task)
     }
 
     fn namespace(&self) -> String {
@@ -108,6 +314,10 @@ impl Application for Bar {
 
     fn update(&mut self, message: Message) -> Task<Message> {
         match message {
+            Message::SwitchWorkspace(id) if self.preview_themes => {
+                self.active_workspace = Some(id);
+                Task::none()
+            }
             Message::SwitchWorkspace(id) => Task::future(async move {
                 hyprland::commands::Command::new()
                     .await
@@ -128,29 +338,92 @@ impl Application for Bar {
                 | HyprlandEvent::DestroyWorkspace { .. }
                 | HyprlandEvent::MoveWorkspace { .. }
                 | HyprlandEvent::RenameWorkspace { .. },
-            ) => fetch_workspaces(),
+            ) => self.fetch_workspaces(),
 
+            Message::HyprlandEvent(HyprlandEvent::OpenWindow { address, .. }) => {
+                self.fetch_client(address)
+            }
+            Message::HyprlandEvent(HyprlandEvent::CloseWindow { address }) => {
+                self.clients.retain(|client| client.address != address);
+                self.pending_clients.remove(&address);
+                Task::none()
+            }
+            Message::HyprlandEvent(HyprlandEvent::MoveWindow {
+                address,
+                workspace_id,
+                workspace,
+            }) => match self.client_mut(address) {
+                Some(client) => {
+                    client.workspace = WorkspaceRef {
+                        id: workspace_id,
+                        name: workspace,
+                    };
+                    Task::none()
+                }
+                None => self.fetch_client(address),
+            },
+            Message::HyprlandEvent(HyprlandEvent::WindowTitle { address, title }) => {
+                match self.client_mut(address) {
+                    Some(client) => {
+                        client.title = title;
+                        Task::none()
+                    }
+                    None => self.fetch_client(address),
+                }
+            }
+            Message::HyprlandEvent(HyprlandEvent::ChangeFloatingMode { address, floating }) => {
+                match self.client_mut(address) {
+                    Some(client) => {
+                        client.floating = floating;
+                        Task::none()
+                    }
+                    None => self.fetch_client(address),
+                }
+            }
+            Message::HyprlandEvent(HyprlandEvent::Pin { address, pinned }) => {
+                match self.client_mut(address) {
+                    Some(client) => {
+                        client.pinned = pinned;
+                        Task::none()
+                    }
+                    None => self.fetch_client(address),
+                }
+            }
+            // the event only says whether fullscreen was entered, not which mode or for which
+            // window, so refetch the focused client
+            Message::HyprlandEvent(HyprlandEvent::Fullscreen { .. }) => match self.active_window {
+                Some(address) => self.fetch_client(address),
+                None => Task::none(),
+            },
+            // group changes touch every member of the group
             Message::HyprlandEvent(
-                HyprlandEvent::MoveWindow { .. }
-                | HyprlandEvent::OpenWindow { .. }
-                | HyprlandEvent::CloseWindow { .. }
-                | HyprlandEvent::WindowTitle { .. },
-            ) => fetch_clients(),
-
-            Message::HyprlandEvent(HyprlandEvent::ActiveWindow {
-                address: Some(address),
-            }) => {
-                self.active_window_title = self
-                    .clients
-                    .iter()
-                    .find(|client| client.address == address)
-                    .map(|client| client.title.clone());
+                HyprlandEvent::ToggleGroup { .. }
+                | HyprlandEvent::MoveIntoGroup { .. }
+                | HyprlandEvent::MoveOutOfGroup { .. },
+            ) => self.fetch_clients(),
 
-                Task::none()
+            Message::HyprlandEvent(HyprlandEvent::ActiveWindow { address }) => {
+                self.active_window = address;
+
+                match address {
+                    // the client cache may not have caught up with a newly opened window yet
+                    Some(address) if self.client(address).is_none() => self.fetch_client(address),
+                    _ => Task::none(),
+                }
             }
-            Message::HyprlandEvent(HyprlandEvent::ActiveWindow { address: None }) => {
-                self.active_window_title = None;
-                Task::none()
+
+            Message::HyprlandEvent(HyprlandEvent::ConfigReloaded) => fetch_config_errors(),
+
+            // hyprland may have been restarted, so nothing we know about it can be trusted
+            Message::HyprlandEvent(HyprlandEvent::Reconnected) => {
+                self.pending_clients.clear();
+
+                Task::batch([
+                    self.fetch_workspaces(),
+                    self.fetch_clients(),
+                    fetch_active_window(),
+                    fetch_config_errors(),
+                ])
             }
 
             Message::HyprlandEvent(_) => Task::none(),
@@ -163,6 +436,40 @@ impl Application for Bar {
                 self.clients = clients;
                 Task::none()
             }
+            Message::UpdateClient(address, client) => {
+                let wanted = self.pending_clients.remove(&address);
+
+                if let Some(client) = client.filter(|_| wanted) {
+                    self.upsert_client(client);
+                }
+
+                Task::none()
+            }
+            Message::UpdateActiveWindow(address) => {
+                self.active_window = address;
+                Task::none()
+            }
+            Message::UpdateConfigErrors(errors) => {
+                self.config_errors = errors;
+                Task::none()
+            }
+            Message::CommandFailed(name, error) => {
+                eprintln!("Error: {name} query failed: {error}");
+                Task::none()
+            }
+            Message::Watchdog => {
+                if let Err(e) = systemd::notify("WATCHDOG=1") {
+                    eprintln!("Error: {:?}", e);
+                }
+
+                Task::none()
+            }
+            Message::NextTheme => {
+                let index = Theme::ALL.iter().position(|theme| *theme == self.theme);
+                let next = index.map_or(0, |index| (index + 1) % Theme::ALL.len());
+                self.theme = Theme::ALL[next].clone();
+                Task::none()
+            }
         }
     }
 
@@ -181,29 +488,49 @@ impl Application for Bar {
                     .on_press(Message::SwitchWorkspace(*id))
                     .into()
             })
-            .chain(std::iter::once(
-                self.active_window_title
-                    .as_deref()
-                    .unwrap_or("No active window")
-                    .into(),
-            )))
+            .chain(std::iter::once(if self.preview_themes {
+                text(format!("{PREVIEW_TITLE} ({})", self.theme)).into()
+            } else {
+                self.active_window
+                    .and_then(|address| self.client(address))
+                    .map_or("No active window", |client| client.title.as_str())
+                    .into()
+            }))
+            .chain(
+                (!self.config_errors.is_empty()).then(|| config_error_badge(&self.config_errors)),
+            ))
         .into()
     }
 
     fn subscription(&self) -> Subscription<Self::Message> {
-        Subscription::run(|| {
-            stream::channel(4, |mut tx| async move {
-                for await event in hyprland::events::EventStream::listen() {
-                    match event {
-                        Ok(event) => tx.send(Message::HyprlandEvent(event)).await.unwrap(),
-                        Err(event) => eprintln!("Error: {:?}", event),
+        let events = match self.preview_themes {
+            true => Subscription::none(),
+            false => Subscription::run(|| {
+                stream::channel(4, |mut tx| async move {
+                    for await event in hyprland::events::EventStream::listen() {
+                        match event {
+                            Ok(event) => tx.send(Message::HyprlandEvent(event)).await.unwrap(),
+                            Err(event) => eprintln!("Error: {:?}", event),
+                        }
                     }
-                }
-            })
-        })
+                })
+            }),
+        };
+
+        let watchdog = match systemd::watchdog_interval() {
+            Some(interval) => iced::time::every(interval).map(|_| Message::Watchdog),
+            None => Subscription::none(),
+        };
+
+        let theme_preview = match self.preview_themes {
+            true => iced::time::every(THEME_PREVIEW_INTERVAL).map(|_| Message::NextTheme),
+            false => Subscription::none(),
+        };
+
+        Subscription::batch([events, watchdog, theme_preview])
     }
 
     fn theme(&self) -> Self::Theme {
-        Theme::TokyoNight
+        self.theme.clone()
     }
 }
@@ -0,0 +1,112 @@
+use std::io;
+use std::os::linux::net::SocketAddrExt as _;
+use std::os::unix::net::{SocketAddr, UnixDatagram};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::Context as _;
+
+/// Sends a state update (e.g. `READY=1`) to the service manager. Does nothing when not started
+/// by systemd with `Type=notify`.
+pub fn notify(state: &str) -> io::Result<()> {
+    let Some(path) = std::env::var_os("NOTIFY_SOCKET") else {
+        return Ok(());
+    };
+
+    let addr = match path.as_encoded_bytes().strip_prefix(b"@") {
+        Some(name) => SocketAddr::from_abstract_name(name)?,
+        None => SocketAddr::from_pathname(&path)?,
+    };
+
+    let socket = UnixDatagram::unbound()?;
+    socket.send_to_addr(state.as_bytes(), &addr)?;
+
+    Ok(())
+}
+
+/// Returns how often `WATCHDOG=1` should be sent, if the watchdog is enabled for this process.
+pub fn watchdog_interval() -> Option<Duration> {
+    if std::env::var("WATCHDOG_PID").is_ok_and(|pid| pid != std::process::id().to_string()) {
+        return None;
+    }
+
+    let usec = std::env::var("WATCHDOG_USEC").ok()?.parse::<u64>().ok()?;
+
+    // ping at half the timeout, as recommended by sd_watchdog_enabled(3)
+    Some(Duration::from_micros(usec / 2))
+}
+
+/// Writes a systemd user unit for rdls and returns its path.
+pub fn install_service() -> anyhow::Result<PathBuf> {
+    let config_dir = match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(std::env::var_os("HOME").context("HOME not set")?).join(".config"),
+    };
+
+    let unit_dir = config_dir.join("systemd").join("user");
+    std::fs::create_dir_all(&unit_dir).context("failed to create unit directory")?;
+
+    let exe = std::env::current_exe().context("failed to get executable path")?;
+    let exe = exe.to_str().context("executable path is not valid UTF-8")?;
+
+    let path = unit_dir.join("rdls.service");
+    std::fs::write(
+        &path,
+        format!(
+            "[Unit]
+Description=rdls status bar
+PartOf=graphical-session.target
+After=graphical-session.target
+Requisite=graphical-session.target
+
+[Service]
+Type=notify
+ExecStart={}
+Restart=on-failure
+WatchdogSec=10
+
+[Install]
+WantedBy=graphical-session.target
+",
+            quote_exec_arg(exe)
+        ),
+    )
+    .context("failed to write unit file")?;
+
+    Ok(path)
+}
+
+/// Quotes an argument for a unit's `Exec*=` line, escaping what systemd would otherwise interpret:
+/// `%` specifiers, `$` variables, and backslashes and quotes within the quotes.
+fn quote_exec_arg(arg: &str) -> String {
+    let mut quoted = String::from("\"");
+
+    for c in arg.chars() {
+        match c {
+            '%' => quoted.push_str("%%"),
+            '$' => quoted.push_str("$$"),
+            '\\' | '"' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            c => quoted.push(c),
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quote_exec_path() {
+        assert_eq!(
+            quote_exec_arg("/home/me/My Apps/100%/rdls"),
+            r#""/home/me/My Apps/100%%/rdls""#
+        );
+        assert_eq!(quote_exec_arg(r#"/a\b"$c"#), r#""/a\\b\"$$c""#);
+    }
+}